@@ -0,0 +1,99 @@
+use crate::individual::{
+    real::{Bounds, Real, RealIndividual},
+    Fitness,
+};
+use crate::problem::Problem;
+use anyhow::{anyhow, Result};
+use rand::RngCore;
+use std::f64::consts::PI;
+
+#[cfg(test)]
+mod tests;
+
+// Continuous benchmark functions, both with the global minimum 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BenchmarkFunction {
+    // Minimum at the origin, usually searched within [-5.12, 5.12]
+    Rastrigin,
+    // Minimum at (1, ..., 1), usually searched within [-5, 10]
+    Rosenbrock,
+}
+
+impl BenchmarkFunction {
+    pub fn value(&self, values: &[Real]) -> Real {
+        match self {
+            Self::Rastrigin => {
+                10.0 * values.len() as Real
+                    + values
+                        .iter()
+                        .map(|x| x * x - 10.0 * (2.0 * PI * x).cos())
+                        .sum::<Real>()
+            }
+            Self::Rosenbrock => values
+                .windows(2)
+                .map(|pair| 100.0 * (pair[1] - pair[0] * pair[0]).powi(2) + (1.0 - pair[0]).powi(2))
+                .sum(),
+        }
+    }
+
+    pub fn default_bounds(&self) -> Bounds {
+        match self {
+            Self::Rastrigin => Bounds::new(-5.12, 5.12),
+            Self::Rosenbrock => Bounds::new(-5.0, 10.0),
+        }
+    }
+}
+
+// Minimization of a benchmark function within bounds, given for every variable
+#[derive(Debug)]
+pub struct ContinuousProblem {
+    function: BenchmarkFunction,
+    bounds: Vec<Bounds>,
+}
+
+impl Problem<RealIndividual> for ContinuousProblem {
+    fn random_individual(&self, rng: &mut dyn RngCore) -> RealIndividual {
+        RealIndividual::random(&self.bounds, rng)
+    }
+
+    fn eval(&self, individual: &RealIndividual) -> Result<Fitness> {
+        self.value(individual)
+    }
+
+    fn serialize_indiviual(&self, individual: &RealIndividual) -> String {
+        match self.value(individual) {
+            Ok(value) => format!("{:?}\nValue: {}\n", individual.values(), value),
+            Err(err) => format!("Invalid individual: {}", err),
+        }
+    }
+}
+
+impl ContinuousProblem {
+    pub fn new(function: BenchmarkFunction, bounds: Vec<Bounds>) -> Self {
+        ContinuousProblem { function, bounds }
+    }
+
+    // Uses the customary search range of the function for every variable
+    pub fn with_dimension(function: BenchmarkFunction, dimension: usize) -> Self {
+        ContinuousProblem::new(function, vec![function.default_bounds(); dimension])
+    }
+
+    pub fn function(&self) -> BenchmarkFunction {
+        self.function
+    }
+
+    pub fn bounds(&self) -> &Vec<Bounds> {
+        &self.bounds
+    }
+
+    pub fn value(&self, individual: &RealIndividual) -> Result<Real> {
+        if individual.dimension() != self.bounds.len() {
+            return Err(anyhow!(
+                "Individual has {} variables, but the problem has {}",
+                individual.dimension(),
+                self.bounds.len()
+            ));
+        }
+        Ok(self.function.value(individual.values()))
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    individual::real::RealIndividual,
+    logger::NoopLogger,
+    neighbor::GaussianNeighborhoodOperator,
+    problem::Problem,
+    simulated_annealing::{
+        algorithm::SimulatedAnnealingBuilder, cooling_schedule::LinearCoolingSchedule,
+        criterion_operator::BoltzmanProbabilityCriterionOperator,
+    },
+    solver::Solver,
+};
+
+use super::{BenchmarkFunction, ContinuousProblem};
+
+#[test]
+fn benchmark_functions_minima() {
+    assert_eq!(BenchmarkFunction::Rastrigin.value(&[0.0, 0.0, 0.0]), 0.0);
+    assert_eq!(BenchmarkFunction::Rosenbrock.value(&[1.0, 1.0, 1.0]), 0.0);
+    assert_eq!(BenchmarkFunction::Rosenbrock.value(&[0.0, 0.0]), 1.0);
+
+    let problem = ContinuousProblem::with_dimension(BenchmarkFunction::Rastrigin, 3);
+    assert_eq!(
+        problem
+            .eval(&RealIndividual::from(vec![0.0, 0.0, 0.0]))
+            .unwrap(),
+        0.0
+    );
+    assert!(problem.eval(&RealIndividual::from(vec![0.0])).is_err());
+}
+
+#[test]
+fn simulated_annealing_on_real_vectors() {
+    let problem = ContinuousProblem::with_dimension(BenchmarkFunction::Rosenbrock, 2);
+    let mut algorithm = SimulatedAnnealingBuilder::new()
+        .iterations(200)
+        .neighbor_operator(Box::new(GaussianNeighborhoodOperator::new(
+            10,
+            0.1,
+            problem.bounds().clone(),
+        )))
+        .cooling_schedule(Box::new(LinearCoolingSchedule::new(200)))
+        .criterion_operator(Box::new(BoltzmanProbabilityCriterionOperator {}))
+        .logger(Box::new(NoopLogger))
+        .build()
+        .unwrap();
+
+    let (fitness, solution) = algorithm.solve(&problem).unwrap();
+
+    assert!(solution
+        .values()
+        .iter()
+        .zip(problem.bounds())
+        .all(|(value, bounds)| (bounds.lower..=bounds.upper).contains(value)));
+    assert_eq!(fitness, problem.eval(&solution).unwrap());
+}
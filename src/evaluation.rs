@@ -0,0 +1,94 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use anyhow::Result;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+use crate::{
+    individual::{Fitness, Individual},
+    problem::Problem,
+};
+
+#[cfg(test)]
+mod tests;
+
+// How solvers evaluate populations and neighborhoods, serially unless configured otherwise.
+// The parallel evaluation splits the work into contiguous chunks and keeps the order of the
+// results, so it never changes a seeded run. Its worker threads are started once and live as long
+// as the solvers sharing them.
+#[derive(Debug, Default, Clone)]
+pub enum Evaluation {
+    #[default]
+    Serial,
+    Parallel(Arc<ThreadPool>),
+}
+
+impl Evaluation {
+    // One thread for every available core
+    pub fn parallel() -> Result<Self> {
+        Ok(Evaluation::Parallel(Arc::new(
+            ThreadPoolBuilder::new().build()?,
+        )))
+    }
+
+    pub fn with_threads(threads: NonZeroUsize) -> Result<Self> {
+        Ok(Evaluation::Parallel(Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(threads.get())
+                .build()?,
+        )))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Evaluation::Serial => String::from("serial"),
+            Evaluation::Parallel(pool) => format!("parallel ({})", pool.current_num_threads()),
+        }
+    }
+
+    // Objective values of the individuals, which are cached in them as well
+    pub fn fitness<I: Individual>(
+        &self,
+        problem: &dyn Problem<I>,
+        individuals: &[I],
+    ) -> Result<Vec<Fitness>> {
+        self.map_chunks(individuals, |chunk| {
+            chunk
+                .iter()
+                .map(|individual| individual.fitness(problem))
+                .collect()
+        })
+    }
+
+    // Every chunk of moves is scored in one call, so problems can still share the work done
+    // on the individual within a chunk
+    pub fn move_values<I: Individual>(
+        &self,
+        problem: &dyn Problem<I>,
+        individual: &I,
+        steps: &[I::Move],
+    ) -> Result<Vec<Fitness>> {
+        // Evaluated once before the threads start, so they all reuse the cached value
+        individual.fitness(problem)?;
+        self.map_chunks(steps, |chunk| problem.eval_moves(individual, chunk))
+    }
+
+    fn map_chunks<T: Sync, F>(&self, items: &[T], map: F) -> Result<Vec<Fitness>>
+    where
+        F: Fn(&[T]) -> Result<Vec<Fitness>> + Sync,
+    {
+        let pool = match self {
+            Evaluation::Parallel(pool) if items.len() > 1 => pool,
+            _ => return map(items),
+        };
+        let chunk_size = items.len().div_ceil(pool.current_num_threads());
+
+        let chunks = pool.install(|| {
+            items
+                .par_chunks(chunk_size)
+                .map(&map)
+                .collect::<Result<Vec<Vec<Fitness>>>>()
+        })?;
+        Ok(chunks.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,75 @@
+use std::num::NonZeroUsize;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    individual::VecIndividual,
+    logger::NoopLogger,
+    neighbor::{NeighborOperator, RelocateNeighborhoodOperator, SwapNeighborhoodOperator},
+    problem::Problem,
+    problem_loader::CVRProblem,
+    solver::Solver,
+    tabu_search::algorithm::TabuSearchBuilder,
+};
+
+use super::Evaluation;
+
+fn parallel(threads: usize) -> Evaluation {
+    Evaluation::with_threads(NonZeroUsize::new(threads).unwrap()).unwrap()
+}
+
+fn random_individuals(problem: &CVRProblem, count: usize) -> Vec<VecIndividual> {
+    let mut rng = StdRng::seed_from_u64(0);
+    (0..count)
+        .map(|_| problem.random_individual(&mut rng))
+        .collect()
+}
+
+#[test]
+fn parallel_evaluation_keeps_the_order() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/A-n32-k5.txt")).unwrap();
+    let serial = Evaluation::Serial
+        .fitness(&problem, &random_individuals(&problem, 25))
+        .unwrap();
+
+    // More threads than individuals leave some of them without work
+    for threads in [1, 3, 8, 64] {
+        let individuals = random_individuals(&problem, 25);
+        assert_eq!(
+            parallel(threads).fitness(&problem, &individuals).unwrap(),
+            serial
+        );
+    }
+
+    let individual = &random_individuals(&problem, 1)[0];
+    let steps =
+        RelocateNeighborhoodOperator::new(50).get_moves(individual, &mut StdRng::seed_from_u64(0));
+    assert_eq!(
+        parallel(4)
+            .move_values(&problem, individual, &steps)
+            .unwrap(),
+        Evaluation::Serial
+            .move_values(&problem, individual, &steps)
+            .unwrap()
+    );
+}
+
+#[test]
+fn parallel_evaluation_gives_identical_runs() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/A-n32-k5.txt")).unwrap();
+    let [serial_run, parallel_run] = [Evaluation::Serial, parallel(4)].map(|evaluation| {
+        TabuSearchBuilder::default()
+            .iterations(20)
+            .tabu_list_size(5)
+            .neighborhood_operator(Box::new(SwapNeighborhoodOperator::new(40)))
+            .logger(Box::new(NoopLogger))
+            .evaluation(evaluation)
+            .seed(3)
+            .build()
+            .unwrap()
+            .solve(&problem)
+            .unwrap()
+    });
+
+    assert_eq!(serial_run, parallel_run);
+}
@@ -1,12 +1,14 @@
 use anyhow::{Context, Result};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 
 use crate::{
-    individual::{Fitness, VecIndividual},
-    logger::{inverse_fitness, CSVEntry, PersistableLogger},
+    evaluation::Evaluation,
+    individual::{Fitness, Individual, VecIndividual},
+    logger::{CSVEntry, PersistableLogger},
     population::Population,
     problem::Problem,
-    solver::Solver,
+    solver::{random_seed, Seed, Solver},
+    termination::{EvaluationCounter, Progress, TerminationCriterion},
 };
 
 use super::{crossover::CrossoverOperator, mutation::Mutation, selection::Selector};
@@ -23,16 +25,16 @@ pub struct GenerationInfo {
 }
 
 impl GenerationInfo {
-    pub fn new(
+    pub fn new<I: Individual>(
         configuration_name: String,
         index: usize,
         crossovers: usize,
         mutations: usize,
-        population: &Population,
-        problem: &dyn Problem,
+        population: &Population<I>,
+        problem: &dyn Problem<I>,
     ) -> Self {
-        let (_, best_fitness) = population.highest_fitness(problem);
-        let (_, worst_fitness) = population.lowest_fitness(problem);
+        let (_, best_fitness) = population.best(problem);
+        let (_, worst_fitness) = population.worst(problem);
         let average_fitness = population.average_fitness(problem);
         GenerationInfo {
             configuration_name,
@@ -52,9 +54,9 @@ impl From<&GenerationInfo> for CSVEntry {
         CSVEntry::from(vec![
             val.configuration_name.to_string(),
             val.index.to_string(),
-            inverse_fitness(val.best_fitness).to_string(),
-            inverse_fitness(val.average_fitness).to_string(),
-            inverse_fitness(val.worst_fitness).to_string(),
+            val.best_fitness.to_string(),
+            val.average_fitness.to_string(),
+            val.worst_fitness.to_string(),
             val.mutations.to_string(),
             val.crossovers.to_string(),
             val.population_size.to_string(),
@@ -62,57 +64,63 @@ impl From<&GenerationInfo> for CSVEntry {
     }
 }
 
-pub struct EvolutionaryAlgorithm {
-    generations: u16,
+pub struct EvolutionaryAlgorithm<I = VecIndividual> {
+    termination: TerminationCriterion,
     population_size: u16,
-    selection_operator: Box<dyn Selector>,
-    mutation_operator: Box<dyn Mutation>,
-    crossover_operator: CrossoverOperator,
+    selection_operator: Box<dyn Selector<I>>,
+    mutation_operator: Box<dyn Mutation<I>>,
+    crossover_operator: CrossoverOperator<I>,
     crossover_prob: f32,
     mutation_prob: f32,
     logger: Box<dyn PersistableLogger<GenerationInfo>>,
+    evaluation: Evaluation,
+    seed: Seed,
 }
 
-impl EvolutionaryAlgorithm {
+impl<I: Individual> EvolutionaryAlgorithm<I> {
     fn configuration_name(&self) -> String {
         format!(
-            "population: {}, generations: {}, selection: {}, mutation: {}, crossover: {}, cross_pob: {}, mut prob: {}",
+            "population: {}, termination: {}, selection: {}, mutation: {}, crossover: {}, cross_pob: {}, mut prob: {}, seed: {}",
             self.population_size,
-            self.generations,
+            self.termination.name(),
             self.selection_operator.name(),
             self.mutation_operator.name(),
             self.crossover_operator.name(),
             self.crossover_prob,
-            self.mutation_prob
+            self.mutation_prob,
+            self.seed
         )
     }
-    fn create_generation(&self, problem: &dyn Problem) -> Population {
-        let initial_solutions: Vec<VecIndividual> = (0..self.population_size)
-            .map(|_| problem.random_individual())
+    fn create_generation(&self, problem: &dyn Problem<I>, rng: &mut dyn RngCore) -> Population<I> {
+        let initial_solutions: Vec<I> = (0..self.population_size)
+            .map(|_| problem.random_individual(rng))
             .collect();
 
         Population::new(initial_solutions)
     }
 
-    fn crossover(&self, mut population: Population) -> Result<(Population, usize)> {
-        let mut rng = rand::thread_rng();
+    fn crossover(
+        &self,
+        mut population: Population<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Population<I>, usize)> {
         let mut children_population = Population::default();
         let mut crossover_count = 0;
         for _ in 0..population.number_of_solutions() / 2 {
             let are_crossed = rng.gen_range(0.0..1.0);
             if are_crossed < self.crossover_prob {
-                let parent_a = population.random_individual();
-                let parent_b = population.random_individual();
+                let parent_a = population.random_individual(rng);
+                let parent_b = population.random_individual(rng);
 
                 match &self.crossover_operator {
                     CrossoverOperator::SingleChildCrossoverOperator(operator) => {
-                        let mut child = operator.crossover(parent_a, parent_b);
+                        let mut child = operator.crossover(parent_a, parent_b, rng);
                         children_population.add_individual(child);
-                        child = operator.crossover(parent_a, parent_b);
+                        child = operator.crossover(parent_a, parent_b, rng);
                         children_population.add_individual(child);
                     }
                     CrossoverOperator::TwoChildrenCrossoverOperator(operator) => {
-                        let (child_a, child_b) = operator.crossover(parent_a, parent_b)?;
+                        let (child_a, child_b) = operator.crossover(parent_a, parent_b, rng)?;
                         children_population.add_individual(child_a);
                         children_population.add_individual(child_b);
                     }
@@ -127,15 +135,14 @@ impl EvolutionaryAlgorithm {
         Ok((population, crossover_count))
     }
 
-    fn mutate(&self, population: &mut Population) -> usize {
+    fn mutate(&self, population: &mut Population<I>, rng: &mut dyn RngCore) -> usize {
         let sp = &self.mutation_operator;
-        let mut rng = rand::thread_rng();
         let mut mutation_count = 0;
 
         for individual in population.mut_solutions().iter_mut() {
             let is_mutated = rng.gen_range(0.0..1.0);
             if is_mutated < self.mutation_prob {
-                sp.mutate(individual);
+                sp.mutate(individual, rng);
                 mutation_count += 1;
             }
         }
@@ -143,62 +150,83 @@ impl EvolutionaryAlgorithm {
     }
 }
 
-impl Solver for EvolutionaryAlgorithm {
-    fn solve(&mut self, problem: &dyn Problem) -> Result<(Fitness, VecIndividual)> {
-        let mut population = self.create_generation(problem);
-        let mut best_solution: Option<VecIndividual> = None;
-        let mut best_solution_fitness: Option<Fitness> = None;
-        for idx in 0..self.generations {
-            let (current_best, current_best_fitness) = population.highest_fitness(problem);
-
-            match (&best_solution, best_solution_fitness) {
-                (None, None) => {
-                    best_solution = Some(current_best.into());
-                    best_solution_fitness = Some(current_best_fitness);
-                }
-                (Some(_), Some(fitness)) if current_best_fitness > fitness => {
-                    best_solution = Some(current_best.into());
-                    best_solution_fitness = Some(current_best_fitness);
-                }
-                _ => {}
-            }
-
-            population = self.selection_operator.select(population, problem)?;
-            let (mut new_population, crossovers) = self.crossover(population)?;
-            let mutations = self.mutate(&mut new_population);
+impl<I: Individual> Solver<I> for EvolutionaryAlgorithm<I> {
+    fn solve(&mut self, problem: &dyn Problem<I>) -> Result<(Fitness, I)> {
+        let problem = &EvaluationCounter::new(problem);
+        let mut progress = Progress::new(problem.sense());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut population = self.create_generation(problem, &mut rng);
+        population.evaluate(problem, &self.evaluation)?;
+        let (best, fitness) = population.best(problem);
+        let (mut best_solution, mut best_solution_fitness) = (best.clone(), fitness);
+        while !self.termination.is_met(&progress) {
+            population = self
+                .selection_operator
+                .select(population, problem, &mut rng)?;
+            let (mut new_population, crossovers) = self.crossover(population, &mut rng)?;
+            let mutations = self.mutate(&mut new_population, &mut rng);
             population = new_population;
+            population.evaluate(problem, &self.evaluation)?;
+
+            let (current_best, current_best_fitness) = population.best(problem);
+            if problem
+                .sense()
+                .is_better(current_best_fitness, best_solution_fitness)
+            {
+                best_solution = current_best.clone();
+                best_solution_fitness = current_best_fitness;
+            }
 
             self.logger.log(GenerationInfo::new(
                 self.configuration_name(),
-                idx as usize,
+                progress.iterations() as usize,
                 crossovers,
                 mutations,
                 &population,
                 problem,
             ));
+            progress.record(best_solution_fitness, problem.evaluations());
         }
 
+        self.seed = rng.gen();
         self.logger.flush()?;
-        Ok((
-            best_solution_fitness.context("No solution found!")?,
-            best_solution.context("No solution found!")?,
-        ))
+        Ok((best_solution_fitness, best_solution))
     }
 }
 
-#[derive(Default)]
-pub struct EvolutionaryAlgorithmBuilder {
+pub struct EvolutionaryAlgorithmBuilder<I = VecIndividual> {
     generations: Option<u16>,
+    termination: Option<TerminationCriterion>,
     population_size: Option<u16>,
-    selection_operator: Option<Box<dyn Selector>>,
-    mutation_operator: Option<Box<dyn Mutation>>,
-    crossover_operator: Option<CrossoverOperator>,
+    selection_operator: Option<Box<dyn Selector<I>>>,
+    mutation_operator: Option<Box<dyn Mutation<I>>>,
+    crossover_operator: Option<CrossoverOperator<I>>,
     crossover_prob: Option<f32>,
     mutation_prob: Option<f32>,
     logger: Option<Box<dyn PersistableLogger<GenerationInfo>>>,
+    evaluation: Option<Evaluation>,
+    seed: Option<Seed>,
+}
+
+impl<I> Default for EvolutionaryAlgorithmBuilder<I> {
+    fn default() -> Self {
+        EvolutionaryAlgorithmBuilder {
+            generations: None,
+            termination: None,
+            population_size: None,
+            selection_operator: None,
+            mutation_operator: None,
+            crossover_operator: None,
+            crossover_prob: None,
+            mutation_prob: None,
+            logger: None,
+            evaluation: None,
+            seed: None,
+        }
+    }
 }
 
-impl EvolutionaryAlgorithmBuilder {
+impl<I> EvolutionaryAlgorithmBuilder<I> {
     pub fn new() -> Self {
         EvolutionaryAlgorithmBuilder::default()
     }
@@ -213,17 +241,17 @@ impl EvolutionaryAlgorithmBuilder {
         self
     }
 
-    pub fn selection_operator(mut self, selection_operator: Box<dyn Selector>) -> Self {
+    pub fn selection_operator(mut self, selection_operator: Box<dyn Selector<I>>) -> Self {
         self.selection_operator = Some(selection_operator);
         self
     }
 
-    pub fn mutation_operator(mut self, mutation_operator: Box<dyn Mutation>) -> Self {
+    pub fn mutation_operator(mut self, mutation_operator: Box<dyn Mutation<I>>) -> Self {
         self.mutation_operator = Some(mutation_operator);
         self
     }
 
-    pub fn crossover_operator(mut self, crossover_operator: CrossoverOperator) -> Self {
+    pub fn crossover_operator(mut self, crossover_operator: CrossoverOperator<I>) -> Self {
         self.crossover_operator = Some(crossover_operator);
         self
     }
@@ -243,9 +271,28 @@ impl EvolutionaryAlgorithmBuilder {
         self
     }
 
-    pub fn build(self) -> Result<EvolutionaryAlgorithm> {
+    pub fn termination(mut self, termination: TerminationCriterion) -> Self {
+        self.termination = Some(termination);
+        self
+    }
+
+    pub fn evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = Some(evaluation);
+        self
+    }
+
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Result<EvolutionaryAlgorithm<I>> {
         Ok(EvolutionaryAlgorithm {
-            generations: self.generations.context("Missing number of generations")?,
+            termination: TerminationCriterion::with_iterations(
+                self.termination,
+                self.generations.map(u64::from),
+            )
+            .context("Missing number of generations")?,
             population_size: self
                 .population_size
                 .context("Missing number of generations")?,
@@ -263,6 +310,8 @@ impl EvolutionaryAlgorithmBuilder {
                 .crossover_prob
                 .context("Missing crossover probability")?,
             logger: self.logger.context("Missing logger")?,
+            evaluation: self.evaluation.unwrap_or_default(),
+            seed: self.seed.unwrap_or_else(random_seed),
         })
     }
 }
@@ -1,35 +1,36 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use anyhow::{anyhow, Result};
 
-use crate::individual::{Gene, VecIndividual};
+use rand::{Rng, RngCore};
+
+use crate::individual::{bitstring::BitIndividual, real::RealIndividual, Gene, VecIndividual};
+
+#[cfg(test)]
 mod tests;
 
-pub trait SingleChildCrossoverOperator {
-    fn crossover(
-        &self,
-        individual_a: &VecIndividual,
-        individual_b: &VecIndividual,
-    ) -> VecIndividual;
+pub trait SingleChildCrossoverOperator<I = VecIndividual> {
+    fn crossover(&self, individual_a: &I, individual_b: &I, rng: &mut dyn RngCore) -> I;
     fn name(&self) -> String;
 }
 
-pub trait TwoChildrenCrossoverOperator {
+pub trait TwoChildrenCrossoverOperator<I = VecIndividual> {
     fn crossover(
         &self,
-        individual_a: &VecIndividual,
-        individual_b: &VecIndividual,
-    ) -> Result<(VecIndividual, VecIndividual)>;
+        individual_a: &I,
+        individual_b: &I,
+        rng: &mut dyn RngCore,
+    ) -> Result<(I, I)>;
 
     fn name(&self) -> String;
 }
 
-pub enum CrossoverOperator {
-    SingleChildCrossoverOperator(Box<dyn SingleChildCrossoverOperator>),
-    TwoChildrenCrossoverOperator(Box<dyn TwoChildrenCrossoverOperator>),
+pub enum CrossoverOperator<I = VecIndividual> {
+    SingleChildCrossoverOperator(Box<dyn SingleChildCrossoverOperator<I>>),
+    TwoChildrenCrossoverOperator(Box<dyn TwoChildrenCrossoverOperator<I>>),
 }
 
-impl CrossoverOperator {
+impl<I> CrossoverOperator<I> {
     pub fn name(&self) -> String {
         match self {
             Self::SingleChildCrossoverOperator(operator) => operator.name(),
@@ -48,8 +49,9 @@ impl SingleChildCrossoverOperator for OrderedCrossover {
         &self,
         individual_a: &VecIndividual,
         individual_b: &VecIndividual,
+        rng: &mut dyn RngCore,
     ) -> VecIndividual {
-        let (start_index, end_index) = individual_a.random_gene_range_indexes();
+        let (start_index, end_index) = individual_a.random_gene_range_indexes(rng);
         // let (start_index, end_index) = (2, 5);
         let substring = individual_a.get_genes_from_range(start_index, end_index);
 
@@ -75,8 +77,8 @@ impl PartiallyMappedCrossover {
         &self,
         parent_a: &[Gene],
         parent_b: &[Gene],
-        translation_a: &mut HashMap<Gene, Vec<Gene>>,
-        translation_b: &mut HashMap<Gene, Vec<Gene>>,
+        translation_a: &mut BTreeMap<Gene, Vec<Gene>>,
+        translation_b: &mut BTreeMap<Gene, Vec<Gene>>,
     ) {
         for (gene_a, gene_b) in parent_a.iter().zip(parent_b) {
             match translation_a.get_mut(gene_a) {
@@ -95,7 +97,7 @@ impl PartiallyMappedCrossover {
         }
     }
 
-    fn flatten_repeated_genes(&self, translations: &mut HashMap<Gene, Vec<Gene>>) -> Result<()> {
+    fn flatten_repeated_genes(&self, translations: &mut BTreeMap<Gene, Vec<Gene>>) -> Result<()> {
         let duplicated_genes = translations
             .iter()
             .filter(|(_, genes)| genes.len() > 1)
@@ -115,7 +117,7 @@ impl PartiallyMappedCrossover {
 
         Ok(())
     }
-    fn get_translated_gene(&self, translation_map: &HashMap<Gene, Vec<Gene>>, gene: Gene) -> Gene {
+    fn get_translated_gene(&self, translation_map: &BTreeMap<Gene, Vec<Gene>>, gene: Gene) -> Gene {
         match translation_map.get(&gene) {
             None => gene,
             Some(translation) => self.get_translated_gene(translation_map, translation[0]),
@@ -123,7 +125,7 @@ impl PartiallyMappedCrossover {
     }
     fn produce_offspring(
         &self,
-        translation_map: &mut HashMap<Gene, Vec<Gene>>,
+        translation_map: &mut BTreeMap<Gene, Vec<Gene>>,
         parent: &[Gene],
         middle_section_start_index: usize,
         middle_section_to_replace: Vec<Gene>,
@@ -153,11 +155,12 @@ impl TwoChildrenCrossoverOperator for PartiallyMappedCrossover {
         &self,
         individual_a: &VecIndividual,
         individual_b: &VecIndividual,
+        rng: &mut dyn RngCore,
     ) -> Result<(VecIndividual, VecIndividual)> {
-        let (start_index, end_index) = individual_a.random_gene_range_indexes();
+        let (start_index, end_index) = individual_a.random_gene_range_indexes(rng);
 
-        let mut translation_map_a: HashMap<Gene, Vec<Gene>> = HashMap::new();
-        let mut translation_map_b: HashMap<Gene, Vec<Gene>> = HashMap::new();
+        let mut translation_map_a: BTreeMap<Gene, Vec<Gene>> = BTreeMap::new();
+        let mut translation_map_b: BTreeMap<Gene, Vec<Gene>> = BTreeMap::new();
         let middle_section_a = individual_a.get_genes_from_range(start_index, end_index);
         let middle_section_b = individual_b.get_genes_from_range(start_index, end_index);
 
@@ -195,3 +198,62 @@ impl TwoChildrenCrossoverOperator for PartiallyMappedCrossover {
         ))
     }
 }
+
+// Every bit is taken from a randomly chosen parent
+pub struct UniformCrossover {}
+
+impl SingleChildCrossoverOperator<BitIndividual> for UniformCrossover {
+    fn name(&self) -> String {
+        String::from("uniform")
+    }
+    fn crossover(
+        &self,
+        individual_a: &BitIndividual,
+        individual_b: &BitIndividual,
+        rng: &mut dyn RngCore,
+    ) -> BitIndividual {
+        BitIndividual::from(
+            individual_a
+                .bits()
+                .iter()
+                .zip(individual_b.bits())
+                .map(|(a, b)| if rng.gen_bool(0.5) { *a } else { *b })
+                .collect::<Vec<bool>>(),
+        )
+    }
+}
+
+// Children are random convex combinations of the parents, so they stay within the parents' bounds
+pub struct ArithmeticCrossover {}
+
+impl TwoChildrenCrossoverOperator<RealIndividual> for ArithmeticCrossover {
+    fn name(&self) -> String {
+        String::from("arithmetic")
+    }
+    fn crossover(
+        &self,
+        individual_a: &RealIndividual,
+        individual_b: &RealIndividual,
+        rng: &mut dyn RngCore,
+    ) -> Result<(RealIndividual, RealIndividual)> {
+        if individual_a.dimension() != individual_b.dimension() {
+            return Err(anyhow!("Parents have different dimensions"));
+        }
+
+        let weight: f64 = rng.gen();
+        let blend = |a: &RealIndividual, b: &RealIndividual| {
+            RealIndividual::from(
+                a.values()
+                    .iter()
+                    .zip(b.values())
+                    .map(|(a, b)| weight * a + (1.0 - weight) * b)
+                    .collect::<Vec<f64>>(),
+            )
+        };
+
+        Ok((
+            blend(individual_a, individual_b),
+            blend(individual_b, individual_a),
+        ))
+    }
+}
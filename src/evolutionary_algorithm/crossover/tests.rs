@@ -3,6 +3,7 @@ use super::{
     TwoChildrenCrossoverOperator,
 };
 use crate::individual::VecIndividual;
+use rand::{rngs::StdRng, SeedableRng};
 
 #[test]
 fn ordered_crossover() {
@@ -11,7 +12,7 @@ fn ordered_crossover() {
 
     let operator = OrderedCrossover {};
 
-    let child = operator.crossover(&parent_a, &parent_b);
+    let child = operator.crossover(&parent_a, &parent_b, &mut StdRng::seed_from_u64(0));
 
     let count = child
         .genes()
@@ -29,7 +30,7 @@ fn partial_ordered_crossover() {
 
     let operator = PartiallyMappedCrossover {};
 
-    let child = operator.crossover(&parent_a, &parent_b);
+    let child = operator.crossover(&parent_a, &parent_b, &mut StdRng::seed_from_u64(0));
     match child {
         Err(err) => {
             panic!("Failed to get children {}", err);
@@ -1,7 +1,14 @@
-use crate::individual::VecIndividual;
+use crate::individual::{
+    bitstring::BitIndividual,
+    moves::Move,
+    real::{Bounds, Real, RealIndividual},
+    Individual, VecIndividual,
+};
+use crate::pdp::pairs::PickupDeliveryPairs;
+use rand::RngCore;
 
-pub trait Mutation {
-    fn mutate(&self, individual: &mut VecIndividual);
+pub trait Mutation<I = VecIndividual> {
+    fn mutate(&self, individual: &mut I, rng: &mut dyn RngCore);
     fn name(&self) -> String;
 }
 
@@ -11,9 +18,9 @@ impl Mutation for SwapMutation {
     fn name(&self) -> String {
         String::from("swap")
     }
-    fn mutate(&self, individual: &mut VecIndividual) {
-        let start_index = individual.random_gene_index();
-        let end_index = individual.random_gene_index();
+    fn mutate(&self, individual: &mut VecIndividual, rng: &mut dyn RngCore) {
+        let start_index = individual.random_gene_index(rng);
+        let end_index = individual.random_gene_index(rng);
 
         individual.genes_mut().swap(start_index, end_index);
     }
@@ -25,10 +32,82 @@ impl Mutation for InverseMutation {
     fn name(&self) -> String {
         String::from("inverse")
     }
-    fn mutate(&self, individual: &mut VecIndividual) {
-        let (start_index, end_index) = individual.random_gene_range_indexes();
-        for index in start_index..(start_index + end_index) / 2 {
-            individual.genes_mut().swap(index, end_index - index);
+    fn mutate(&self, individual: &mut VecIndividual, rng: &mut dyn RngCore) {
+        let (start_index, end_index) = individual.random_gene_range_indexes(rng);
+        individual.apply_move(&Move::TwoOpt(start_index, end_index));
+    }
+}
+
+// Precedence preserving mutations for pickup and delivery problems
+pub struct PairSwapMutation {
+    pairs: PickupDeliveryPairs,
+}
+
+impl PairSwapMutation {
+    pub fn new(pairs: PickupDeliveryPairs) -> Self {
+        PairSwapMutation { pairs }
+    }
+}
+
+impl Mutation for PairSwapMutation {
+    fn name(&self) -> String {
+        String::from("pair swap")
+    }
+    fn mutate(&self, individual: &mut VecIndividual, rng: &mut dyn RngCore) {
+        self.pairs.swap_random_pairs(individual, rng);
+    }
+}
+
+pub struct PairRelocateMutation {
+    pairs: PickupDeliveryPairs,
+}
+
+impl PairRelocateMutation {
+    pub fn new(pairs: PickupDeliveryPairs) -> Self {
+        PairRelocateMutation { pairs }
+    }
+}
+
+impl Mutation for PairRelocateMutation {
+    fn name(&self) -> String {
+        String::from("pair relocate")
+    }
+    fn mutate(&self, individual: &mut VecIndividual, rng: &mut dyn RngCore) {
+        self.pairs.relocate_random_pair(individual, rng);
+    }
+}
+
+pub struct BitFlipMutation {}
+
+impl Mutation<BitIndividual> for BitFlipMutation {
+    fn name(&self) -> String {
+        String::from("bit flip")
+    }
+    fn mutate(&self, individual: &mut BitIndividual, rng: &mut dyn RngCore) {
+        let index = individual.random_bit_index(rng);
+        individual.flip(index);
+    }
+}
+
+// Gaussian noise on every variable, bounds are given per variable
+pub struct GaussianMutation {
+    sigma: Real,
+    bounds: Vec<Bounds>,
+}
+
+impl GaussianMutation {
+    pub fn new(sigma: Real, bounds: Vec<Bounds>) -> Self {
+        GaussianMutation { sigma, bounds }
+    }
+}
+
+impl Mutation<RealIndividual> for GaussianMutation {
+    fn name(&self) -> String {
+        format!("gaussian {}", self.sigma)
+    }
+    fn mutate(&self, individual: &mut RealIndividual, rng: &mut dyn RngCore) {
+        for (index, bounds) in self.bounds.iter().enumerate() {
+            individual.perturb(index, self.sigma, bounds, rng);
         }
     }
 }
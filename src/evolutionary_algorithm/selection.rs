@@ -2,14 +2,22 @@ use anyhow::{Context, Result};
 use rand::{seq::SliceRandom, RngCore};
 
 use crate::{
-    individual::{Fitness, VecIndividual},
+    individual::{Fitness, Individual, VecIndividual},
     population::Population,
-    problem::Problem,
+    problem::{ObjectiveSense, Problem},
     simulated_annealing::algorithm::Temperature,
 };
 
-pub trait Selector {
-    fn select(&mut self, population: Population, problem: &dyn Problem) -> Result<Population>;
+#[cfg(test)]
+mod tests;
+
+pub trait Selector<I = VecIndividual> {
+    fn select(
+        &mut self,
+        population: Population<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Population<I>>;
     fn name(&self) -> String;
 }
 
@@ -23,24 +31,28 @@ impl TournamentSelector {
     }
 }
 
-impl Selector for TournamentSelector {
+impl<I: Individual> Selector<I> for TournamentSelector {
     fn name(&self) -> String {
         format!("tournament of {}", self.size)
     }
-    fn select(&mut self, population: Population, problem: &dyn Problem) -> Result<Population> {
-        let mut next_generation: Vec<VecIndividual> = Vec::new();
+    fn select(
+        &mut self,
+        population: Population<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Population<I>> {
+        let mut next_generation: Vec<I> = Vec::new();
 
         for _ in 0..population.solutions().len() {
-            let tournament: Vec<VecIndividual> = population
+            let tournament: Vec<I> = population
                 .solutions()
-                .choose_multiple(&mut rand::thread_rng(), self.size as usize)
-                .map(|solution| solution.into())
+                .choose_multiple(rng, self.size as usize)
+                .cloned()
                 .collect();
 
-            let (best_solution, _) =
-                Population::individual_with_highest_fitness(problem, &tournament);
+            let (best_solution, _) = Population::best_of(problem, &tournament);
 
-            next_generation.push(VecIndividual::from(best_solution));
+            next_generation.push(best_solution.clone());
         }
 
         Ok(Population::new(next_generation))
@@ -71,18 +83,18 @@ impl SimulatedAnnealingRouletteSelector {
 }
 
 impl RouletteSelector {
-    fn find_individual_by_probability<'b>(
+    fn find_individual_by_probability<'b, I: Individual>(
         &self,
-        population: &'b Population,
-        probabilities: &[f32],
-        selected_probability: f32,
-    ) -> Result<&'b VecIndividual> {
-        let mut summed_probability = 0f32;
+        population: &'b Population<I>,
+        probabilities: &[Fitness],
+        selected_probability: Fitness,
+    ) -> Result<&'b I> {
+        let mut summed_probability: Fitness = 0.0;
 
         let populations_iter =
             (0..population.number_of_solutions()).zip(1..population.number_of_solutions());
 
-        for (individual_idx, next_individual_idx) in populations_iter {
+        for (individual_idx, _) in populations_iter {
             if selected_probability >= summed_probability
                 && selected_probability < summed_probability + probabilities[individual_idx]
             {
@@ -99,49 +111,39 @@ impl RouletteSelector {
     }
 }
 
-impl Selector for RouletteSelector {
+impl<I: Individual> Selector<I> for RouletteSelector {
     fn name(&self) -> String {
         String::from("roulette")
     }
-    fn select(&mut self, population: Population, problem: &dyn Problem) -> Result<Population> {
-        let mut next_generation: Vec<VecIndividual> = Vec::new();
-        let mut probabilities: Vec<f32> = Vec::new();
-        let mut rng = rand::thread_rng();
+    fn select(
+        &mut self,
+        population: Population<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Population<I>> {
+        let mut next_generation: Vec<I> = Vec::new();
+        let mut probabilities: Vec<Fitness> = Vec::new();
 
         let scores: Vec<Fitness> = population
             .solutions()
             .iter()
-            .map(|sol| problem.eval(sol))
+            .map(|sol| sol.fitness(problem))
             .collect::<Result<Vec<Fitness>>>()?;
 
-        let min_score = scores
-            .iter()
-            .min_by(|x, y| x.total_cmp(y))
-            .context("Failed to compare scores")?;
-        let max_score = scores
-            .iter()
-            .max_by(|x, y| x.total_cmp(y))
-            .context("Failed to compare scores")?;
+        let scores = normalized_qualities(&scores, problem.sense());
+        let scores_sum: Fitness = scores.iter().sum();
 
-        let scores_sum: Fitness = scores
-            .iter()
-            .map(|score| (score - min_score) / (max_score - min_score))
-            .sum();
-
-        for score in scores
-            .iter()
-            .map(|score| (score - min_score) / (max_score - min_score))
-        {
-            let probability: f32 = score / scores_sum;
+        for score in scores {
+            let probability: Fitness = score / scores_sum;
             probabilities.push(probability);
         }
 
         for _ in 0..population.number_of_solutions() {
-            let random_probality: f32 = (rng.next_u32() % 100) as f32 / (100f32);
+            let random_probality: Fitness = (rng.next_u32() % 100) as Fitness / 100.0;
             let child =
                 self.find_individual_by_probability(&population, &probabilities, random_probality)?;
 
-            next_generation.push(VecIndividual::from(child));
+            next_generation.push(child.clone());
         }
 
         Ok(Population::new(next_generation))
@@ -149,18 +151,18 @@ impl Selector for RouletteSelector {
 }
 
 impl SimulatedAnnealingRouletteSelector {
-    fn find_individual_by_probability<'b>(
+    fn find_individual_by_probability<'b, I: Individual>(
         &self,
-        population: &'b Population,
-        probabilities: &[f32],
-        selected_probability: f32,
-    ) -> Result<&'b VecIndividual> {
-        let mut summed_probability = 0f32;
+        population: &'b Population<I>,
+        probabilities: &[Fitness],
+        selected_probability: Fitness,
+    ) -> Result<&'b I> {
+        let mut summed_probability: Fitness = 0.0;
 
         let populations_iter =
             (0..population.number_of_solutions()).zip(1..population.number_of_solutions());
 
-        for (individual_idx, next_individual_idx) in populations_iter {
+        for (individual_idx, _) in populations_iter {
             if selected_probability >= summed_probability
                 && selected_probability < summed_probability + probabilities[individual_idx]
             {
@@ -177,21 +179,27 @@ impl SimulatedAnnealingRouletteSelector {
     }
 }
 
-impl Selector for SimulatedAnnealingRouletteSelector {
+impl<I: Individual> Selector<I> for SimulatedAnnealingRouletteSelector {
     fn name(&self) -> String {
         format!("saea: cooldown: {}", self.cooldown_factor)
     }
-    fn select(&mut self, population: Population, problem: &dyn Problem) -> Result<Population> {
-        let mut next_generation: Vec<VecIndividual> = Vec::new();
-        let mut probabilities: Vec<f32> = Vec::new();
-        let mut rng = rand::thread_rng();
+    fn select(
+        &mut self,
+        population: Population<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Population<I>> {
+        let mut next_generation: Vec<I> = Vec::new();
+        let mut probabilities: Vec<Fitness> = Vec::new();
 
-        let mut scores: Vec<Fitness> = population
+        let scores: Vec<Fitness> = population
             .solutions()
             .iter()
-            .map(|sol| problem.eval(sol))
+            .map(|sol| sol.fitness(problem))
             .collect::<Result<Vec<Fitness>>>()?;
 
+        // The best individual gets more likely to be selected as the temperature drops
+        let mut scores = normalized_qualities(&scores, problem.sense());
         let max_index = scores
             .iter()
             .enumerate()
@@ -199,40 +207,48 @@ impl Selector for SimulatedAnnealingRouletteSelector {
             .map(|(index, _)| index)
             .unwrap();
 
-        scores[max_index] *= 1f32 / self.temperature;
+        scores[max_index] *= 1.0 / self.temperature as Fitness;
 
         self.temperature *= self.cooldown_factor;
 
-        let min_score = scores
-            .iter()
-            .min_by(|x, y| x.total_cmp(y))
-            .context("Failed to compare scores")?;
-        let max_score = scores
-            .iter()
-            .max_by(|x, y| x.total_cmp(y))
-            .context("Failed to compare scores")?;
-
-        let scores_sum: Fitness = scores
-            .iter()
-            .map(|score| (score - min_score) / (max_score - min_score))
-            .sum();
+        let scores_sum: Fitness = scores.iter().sum();
 
-        for score in scores
-            .iter()
-            .map(|score| (score - min_score) / (max_score - min_score))
-        {
-            let probability: f32 = score / scores_sum;
+        for score in scores {
+            let probability: Fitness = score / scores_sum;
             probabilities.push(probability);
         }
 
         for _ in 0..population.number_of_solutions() {
-            let random_probality: f32 = (rng.next_u32() % 100) as f32 / (100f32);
+            let random_probality: Fitness = (rng.next_u32() % 100) as Fitness / 100.0;
             let child =
                 self.find_individual_by_probability(&population, &probabilities, random_probality)?;
 
-            next_generation.push(VecIndividual::from(child));
+            next_generation.push(child.clone());
         }
 
         Ok(Population::new(next_generation))
     }
 }
+
+// Scales the scores to [0, 1], where the best individual gets 1 and the worst 0.
+// Infeasible (non-finite) individuals get 0, and when the feasible ones all score
+// the same they are equally likely to be selected.
+fn normalized_qualities(scores: &[Fitness], sense: ObjectiveSense) -> Vec<Fitness> {
+    let feasible = || scores.iter().copied().filter(|score| score.is_finite());
+    let (Some(best), Some(worst)) = (
+        feasible().max_by(|x, y| sense.compare(*x, *y)),
+        feasible().min_by(|x, y| sense.compare(*x, *y)),
+    ) else {
+        return vec![1.0; scores.len()];
+    };
+
+    let range = sense.improvement(worst, best);
+    scores
+        .iter()
+        .map(|score| match score.is_finite() {
+            false => 0.0,
+            true if range == 0.0 => 1.0,
+            true => sense.improvement(worst, *score) / range,
+        })
+        .collect()
+}
@@ -0,0 +1,24 @@
+use crate::{individual::Fitness, problem::ObjectiveSense};
+
+use super::normalized_qualities;
+
+#[test]
+fn converged_scores_get_uniform_qualities() {
+    let qualities = normalized_qualities(&[42.0; 4], ObjectiveSense::Minimize);
+
+    assert_eq!(qualities, vec![1.0; 4]);
+}
+
+#[test]
+fn infeasible_scores_get_no_quality() {
+    let scores = [10.0, Fitness::INFINITY, 20.0, 15.0];
+
+    let qualities = normalized_qualities(&scores, ObjectiveSense::Minimize);
+    assert_eq!(qualities, vec![1.0, 0.0, 0.0, 0.5]);
+
+    let qualities = normalized_qualities(&[Fitness::INFINITY, 7.0], ObjectiveSense::Minimize);
+    assert_eq!(qualities, vec![0.0, 1.0]);
+
+    let qualities = normalized_qualities(&[Fitness::INFINITY; 3], ObjectiveSense::Minimize);
+    assert_eq!(qualities, vec![1.0; 3]);
+}
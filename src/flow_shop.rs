@@ -0,0 +1,212 @@
+use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::parsing::parse_number;
+use crate::problem::Problem;
+use anyhow::{anyhow, Context, Result};
+use rand::RngCore;
+use std::fs::read_to_string;
+
+#[cfg(test)]
+mod tests;
+
+pub type Duration = u64;
+
+// Value minimized by the schedule
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FlowShopObjective {
+    // Completion time of the last job on the last machine
+    #[default]
+    Makespan,
+    // Sum of the completion times of all jobs
+    TotalFlowTime,
+}
+
+// Permutation flow shop, every job visits the machines in the same order
+// and gene i is the i-th job put into production
+#[derive(Debug, Default)]
+pub struct FlowShopProblem {
+    jobs: usize,
+    machines: usize,
+    // Processing times indexed by machine and job
+    processing_times: Vec<Vec<Duration>>,
+    upper_bound: Option<Duration>,
+    lower_bound: Option<Duration>,
+    objective: FlowShopObjective,
+}
+
+impl Problem for FlowShopProblem {
+    fn random_individual(&self, rng: &mut dyn RngCore) -> VecIndividual {
+        let jobs: Vec<Gene> = (0..self.jobs as Gene).collect();
+        let mut left_nodes = VecIndividual::from(&jobs);
+        let mut selected_nodes: VecIndividual = VecIndividual::new();
+
+        for _ in &jobs {
+            let selected_gene = left_nodes.random_gene(rng);
+            selected_nodes.add_gene(selected_gene);
+            left_nodes.remove_gene(selected_gene)
+        }
+
+        selected_nodes
+    }
+
+    fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
+        let value = match self.objective {
+            FlowShopObjective::Makespan => self.makespan(individual)?,
+            FlowShopObjective::TotalFlowTime => self.total_flow_time(individual)?,
+        };
+        Ok(value as Fitness)
+    }
+
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        match (self.makespan(individual), self.total_flow_time(individual)) {
+            (Ok(makespan), Ok(total_flow_time)) => format!(
+                "Jobs: {}\nMakespan: {}\nTotal flow time: {}\n",
+                individual
+                    .genes()
+                    .iter()
+                    .map(|job| (job + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                makespan,
+                total_flow_time
+            ),
+            (Err(err), _) | (_, Err(err)) => format!("Invalid individual: {}", err),
+        }
+    }
+}
+
+impl FlowShopProblem {
+    // Processing times are given per machine, every row lists all jobs
+    pub fn new(processing_times: Vec<Vec<Duration>>) -> Result<FlowShopProblem> {
+        let jobs = processing_times.first().map_or(0, |row| row.len());
+        if jobs == 0 || processing_times.iter().any(|row| row.len() != jobs) {
+            return Err(anyhow!(
+                "Every machine needs a processing time for each of the jobs"
+            ));
+        }
+
+        Ok(FlowShopProblem {
+            jobs,
+            machines: processing_times.len(),
+            processing_times,
+            ..FlowShopProblem::default()
+        })
+    }
+
+    // Taillard's format, a file may hold several instances, each one starting with a
+    // "jobs machines seed upper_bound lower_bound" header and a "processing times" block
+    pub fn parse_all(content: &str) -> Result<Vec<FlowShopProblem>> {
+        let mut numbers = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                line.trim()
+                    .starts_with(|first: char| first.is_ascii_digit())
+            })
+            .flat_map(|(index, line)| {
+                line.split_whitespace()
+                    .map(move |number| parse_number::<Duration>(number, index + 1))
+            })
+            .peekable();
+
+        let mut problems = Vec::new();
+        while numbers.peek().is_some() {
+            let mut header = || {
+                numbers
+                    .next()
+                    .ok_or(anyhow!("incomplete instance header"))?
+            };
+            let (jobs, machines, _seed) = (header()? as usize, header()? as usize, header()?);
+            let (upper_bound, lower_bound) = (header()?, header()?);
+
+            let processing_times = (0..machines)
+                .map(|_| {
+                    (0..jobs)
+                        .map(|_| {
+                            numbers.next().ok_or(anyhow!(
+                                "expected {} machines with {} processing times",
+                                machines,
+                                jobs
+                            ))?
+                        })
+                        .collect()
+                })
+                .collect::<Result<Vec<Vec<Duration>>>>()?;
+
+            let mut problem = FlowShopProblem::new(processing_times)?;
+            problem.upper_bound = Some(upper_bound);
+            problem.lower_bound = Some(lower_bound);
+            problems.push(problem);
+        }
+
+        Ok(problems)
+    }
+
+    pub fn parse(content: &str) -> Result<FlowShopProblem> {
+        FlowShopProblem::parse_all(content)?
+            .into_iter()
+            .next()
+            .ok_or(anyhow!("file contains no instances"))
+    }
+
+    pub fn from_file(path: &str) -> Result<FlowShopProblem> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        FlowShopProblem::parse(&content).with_context(|| format!("Failed to parse {}", path))
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs
+    }
+
+    pub fn machines(&self) -> usize {
+        self.machines
+    }
+
+    // Best known makespan listed with Taillard instances
+    pub fn upper_bound(&self) -> Option<Duration> {
+        self.upper_bound
+    }
+
+    pub fn lower_bound(&self) -> Option<Duration> {
+        self.lower_bound
+    }
+
+    pub fn objective(&self) -> FlowShopObjective {
+        self.objective
+    }
+
+    pub fn set_objective(&mut self, objective: FlowShopObjective) {
+        self.objective = objective;
+    }
+
+    pub fn makespan(&self, individual: &VecIndividual) -> Result<Duration> {
+        Ok(*self.completion_times(individual)?.last().unwrap_or(&0))
+    }
+
+    pub fn total_flow_time(&self, individual: &VecIndividual) -> Result<Duration> {
+        Ok(self.completion_times(individual)?.iter().sum())
+    }
+
+    // Completion time of every job on the last machine, in the order of the permutation
+    pub fn completion_times(&self, individual: &VecIndividual) -> Result<Vec<Duration>> {
+        let genes = individual.genes();
+        if genes.len() != self.jobs || genes.iter().any(|job| *job as usize >= self.jobs) {
+            return Err(anyhow!("Schedule has to contain all {} jobs", self.jobs));
+        }
+
+        // Completion of the previous job on every machine
+        let mut machine_free = vec![0 as Duration; self.machines];
+        let mut completion_times = Vec::with_capacity(self.jobs);
+
+        for job in genes {
+            let mut previous_machine_done = 0;
+            for (machine, free) in machine_free.iter_mut().enumerate() {
+                *free = (*free).max(previous_machine_done)
+                    + self.processing_times[machine][*job as usize];
+                previous_machine_done = *free;
+            }
+            completion_times.push(previous_machine_done);
+        }
+
+        Ok(completion_times)
+    }
+}
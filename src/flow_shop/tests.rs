@@ -0,0 +1,51 @@
+use crate::{individual::VecIndividual, problem::Problem};
+
+use super::{FlowShopObjective, FlowShopProblem};
+
+const TAILLARD_INSTANCE: &str =
+    "number of jobs, number of machines, initial seed, upper bound and lower bound :
+           3           2   873654221          10           9
+processing times :
+  3  2  4
+  2  5  1
+number of jobs, number of machines, initial seed, upper bound and lower bound :
+           2           1   379008056           7           7
+processing times :
+  3  4
+";
+
+#[test]
+fn parse_taillard_instances() {
+    let problems = FlowShopProblem::parse_all(TAILLARD_INSTANCE).unwrap();
+
+    assert_eq!(problems.len(), 2);
+    assert_eq!((problems[0].jobs(), problems[0].machines()), (3, 2));
+    assert_eq!(problems[0].upper_bound(), Some(10));
+    assert_eq!(problems[1].lower_bound(), Some(7));
+
+    let truncated = TAILLARD_INSTANCE
+        .lines()
+        .take(4)
+        .collect::<Vec<&str>>()
+        .join("\n");
+    assert!(FlowShopProblem::parse(&truncated).is_err());
+}
+
+#[test]
+fn makespan_and_total_flow_time() {
+    let mut problem = FlowShopProblem::parse(TAILLARD_INSTANCE).unwrap();
+    let individual = VecIndividual::from(vec![0, 1, 2]);
+
+    // Machine 1 finishes jobs at 3, 5 and 9, machine 2 at 5, 10 and 11
+    assert_eq!(
+        problem.completion_times(&individual).unwrap(),
+        vec![5, 10, 11]
+    );
+    assert_eq!(problem.makespan(&individual).unwrap(), 11);
+    assert_eq!(problem.total_flow_time(&individual).unwrap(), 26);
+    assert_eq!(problem.eval(&individual).unwrap(), 11.0);
+
+    problem.set_objective(FlowShopObjective::TotalFlowTime);
+    assert_eq!(problem.eval(&individual).unwrap(), 26.0);
+    assert!(problem.eval(&VecIndividual::from(vec![0, 1])).is_err());
+}
@@ -26,7 +26,10 @@ impl<'a> GreedyAlgorithm<'a> {
     pub fn solve(&mut self, first_node: Gene) -> Result<VecIndividual> {
         let initial = TripState {
             current_node: first_node,
-            resources: self.problem.capacity() - self.problem.demands(&first_node)?,
+            resources: self
+                .problem
+                .route_capacity()
+                .saturating_sub(self.problem.demands(&first_node)?),
             visited_nodes: vec![],
             unvisited_nodes: self
                 .problem
@@ -48,8 +51,11 @@ impl<'a> GreedyAlgorithm<'a> {
                     .collect::<Vec<Node>>();
 
                 if self.need_trip_to_depot(&accum, closest_node)? {
-                    accum.resources =
-                        self.problem.capacity() - self.problem.demands(&closest_node)?
+                    // Split deliveries use up the whole vehicle for customers that do not fit
+                    accum.resources = self
+                        .problem
+                        .route_capacity()
+                        .saturating_sub(self.problem.demands(&closest_node)?)
                 } else {
                     accum.resources -= self.problem.demands(&closest_node)?
                 }
@@ -71,13 +77,12 @@ impl<'a> GreedyAlgorithm<'a> {
             .iter()
             .min_by_key(|node| {
                 if self.need_trip_to_depot(state, **node).unwrap() {
+                    let return_depot = self.problem.closest_depot(&state.current_node).unwrap();
+                    let start_depot = self.problem.closest_depot(node).unwrap();
                     self.problem
-                        .distance(&state.current_node, &self.problem.closest_depot())
+                        .distance(&state.current_node, &return_depot)
                         .unwrap() as i32
-                        + self
-                            .problem
-                            .distance(&self.problem.closest_depot(), node)
-                            .unwrap() as i32
+                        + self.problem.distance(&start_depot, node).unwrap() as i32
                 } else {
                     self.problem.distance(&state.current_node, node).unwrap() as i32
                 }
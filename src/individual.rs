@@ -1,11 +1,86 @@
+use std::fmt::Debug;
+use std::sync::OnceLock;
+
+use anyhow::Result;
 use rand::RngCore;
 
+use crate::problem::Problem;
+use moves::Move;
+
+pub mod bitstring;
+pub mod moves;
+pub mod real;
+
 pub type Gene = u16;
-pub type Fitness = f32;
+// Raw objective value, minimized or maximized as declared by the problem
+pub type Fitness = f64;
+
+// Genotype of a solution, solvers work with any of them while operators and
+// problems are typed to the genotypes they support. Individuals and moves are shared
+// with the threads of a parallel evaluation.
+pub trait Individual: Clone + PartialEq + Debug + Send + Sync {
+    // Smallest change neighborhoods are built from
+    type Move: Clone + Debug + Send + Sync;
+
+    fn apply_move(&mut self, step: &Self::Move);
+    fn fitness_cache(&self) -> &FitnessCache;
+
+    // Evaluates the individual at most once, clones share the cached value until mutated.
+    // The cache assumes the individual is always evaluated against the same problem.
+    fn fitness<P: Problem<Self> + ?Sized>(&self, problem: &P) -> Result<Fitness> {
+        if let Some(fitness) = self.fitness_cache().get() {
+            return Ok(fitness);
+        }
+        let fitness = problem.eval(self)?;
+        self.fitness_cache().set(fitness);
+        Ok(fitness)
+    }
+}
+
+// Fitness of the genotype it belongs to, every mutable accessor of an individual clears it.
+// It is not a part of the genotype, so it never affects the equality of individuals.
+#[derive(Debug, Default, Clone)]
+pub struct FitnessCache {
+    fitness: OnceLock<Fitness>,
+}
+
+impl FitnessCache {
+    pub fn get(&self) -> Option<Fitness> {
+        self.fitness.get().copied()
+    }
+
+    pub fn set(&self, fitness: Fitness) {
+        let _ = self.fitness.set(fitness);
+    }
+
+    pub fn invalidate(&mut self) {
+        self.fitness.take();
+    }
+}
+
+impl PartialEq for FitnessCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Individual for VecIndividual {
+    type Move = Move;
 
+    fn apply_move(&mut self, step: &Move) {
+        step.apply(self.genes_mut())
+    }
+
+    fn fitness_cache(&self) -> &FitnessCache {
+        &self.fitness
+    }
+}
+
+// Permutation of genes
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct VecIndividual {
     genes: Vec<Gene>,
+    fitness: FitnessCache,
 }
 
 impl VecIndividual {
@@ -13,28 +88,31 @@ impl VecIndividual {
         &self.genes
     }
     pub fn genes_mut(&mut self) -> &mut Vec<Gene> {
+        self.fitness.invalidate();
         &mut self.genes
     }
     pub fn new() -> Self {
-        VecIndividual { genes: Vec::new() }
+        VecIndividual::default()
     }
 
     pub fn add_gene(&mut self, gene: Gene) {
+        self.fitness.invalidate();
         self.genes.push(gene)
     }
 
     pub fn remove_gene(&mut self, gene: Gene) {
+        self.fitness.invalidate();
         if let Some(index) = self.genes.iter().position(|curr| *curr == gene) {
             self.genes.remove(index);
         };
     }
 
-    pub fn random_gene(&self) -> Gene {
-        self.genes[self.random_gene_index()]
+    pub fn random_gene(&self, rng: &mut dyn RngCore) -> Gene {
+        self.genes[self.random_gene_index(rng)]
     }
 
-    pub fn random_gene_index(&self) -> usize {
-        let random_index = rand::thread_rng().next_u32() as usize;
+    pub fn random_gene_index(&self, rng: &mut dyn RngCore) -> usize {
+        let random_index = rng.next_u32() as usize;
         random_index % self.genes.len()
     }
 
@@ -42,10 +120,8 @@ impl VecIndividual {
         self.genes.len()
     }
 
-    pub fn random_gene_range_indexes(&self) -> (usize, usize) {
+    pub fn random_gene_range_indexes(&self, rng: &mut dyn RngCore) -> (usize, usize) {
         // Make sure that the range always has more than one element
-        let mut rng = rand::thread_rng();
-
         let start_index: usize = rng.next_u32() as usize % (self.number_of_genes() - 1);
         let end_index: usize =
             start_index + 1 + rng.next_u32() as usize % (self.number_of_genes() - start_index - 1);
@@ -63,22 +139,21 @@ impl VecIndividual {
 
 impl From<&Vec<Gene>> for VecIndividual {
     fn from(genes: &Vec<Gene>) -> Self {
-        VecIndividual {
-            genes: genes.clone(),
-        }
+        VecIndividual::from(genes.clone())
     }
 }
 
 impl From<Vec<Gene>> for VecIndividual {
     fn from(genes: Vec<Gene>) -> Self {
-        VecIndividual { genes }
+        VecIndividual {
+            genes,
+            fitness: FitnessCache::default(),
+        }
     }
 }
 
 impl From<&VecIndividual> for VecIndividual {
     fn from(individual: &VecIndividual) -> Self {
-        VecIndividual {
-            genes: individual.genes().clone(),
-        }
+        individual.clone()
     }
 }
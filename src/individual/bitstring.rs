@@ -0,0 +1,67 @@
+use rand::RngCore;
+
+use super::{FitnessCache, Individual};
+
+// Fixed length bitstring, e.g. the selected items of a knapsack or a MaxSAT assignment
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BitIndividual {
+    bits: Vec<bool>,
+    fitness: FitnessCache,
+}
+
+// Negates the bit at the given index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitFlip(pub usize);
+
+impl Individual for BitIndividual {
+    type Move = BitFlip;
+
+    fn apply_move(&mut self, step: &BitFlip) {
+        self.flip(step.0)
+    }
+
+    fn fitness_cache(&self) -> &FitnessCache {
+        &self.fitness
+    }
+}
+
+impl BitIndividual {
+    pub fn random(length: usize, rng: &mut dyn RngCore) -> Self {
+        BitIndividual::from(
+            (0..length)
+                .map(|_| rng.next_u32() % 2 == 1)
+                .collect::<Vec<bool>>(),
+        )
+    }
+
+    pub fn bits(&self) -> &Vec<bool> {
+        &self.bits
+    }
+
+    pub fn bits_mut(&mut self) -> &mut Vec<bool> {
+        self.fitness.invalidate();
+        &mut self.bits
+    }
+
+    pub fn number_of_bits(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn random_bit_index(&self, rng: &mut dyn RngCore) -> usize {
+        rng.next_u32() as usize % self.bits.len()
+    }
+
+    pub fn flip(&mut self, index: usize) {
+        self.fitness.invalidate();
+        self.bits[index] = !self.bits[index];
+    }
+}
+
+impl From<Vec<bool>> for BitIndividual {
+    fn from(bits: Vec<bool>) -> Self {
+        BitIndividual {
+            bits,
+            fitness: FitnessCache::default(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::Gene;
+
+// Change of a permutation, described by positions so it can be scored without applying it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Move {
+    // Exchanges the genes at both positions
+    Swap(usize, usize),
+    // Reverses the genes between both positions (inclusive)
+    TwoOpt(usize, usize),
+    // Removes the gene and inserts it back, `to` is its position in the result
+    Relocate { from: usize, to: usize },
+    // Moves applied one after another
+    Chain(Vec<Move>),
+}
+
+impl Move {
+    pub fn apply(&self, genes: &mut Vec<Gene>) {
+        match self {
+            Move::Swap(a, b) => genes.swap(*a, *b),
+            Move::TwoOpt(a, b) => genes[*a.min(b)..=*a.max(b)].reverse(),
+            Move::Relocate { from, to } => {
+                let gene = genes.remove(*from);
+                genes.insert(*to, gene);
+            }
+            Move::Chain(moves) => moves.iter().for_each(|step| step.apply(genes)),
+        }
+    }
+
+    // Position, before the move, of the gene ending up at the given position
+    pub fn source_index(&self, index: usize) -> usize {
+        match self {
+            Move::Swap(a, b) if index == *a => *b,
+            Move::Swap(a, b) if index == *b => *a,
+            Move::TwoOpt(a, b) if (*a.min(b)..=*a.max(b)).contains(&index) => a + b - index,
+            Move::Relocate { from, to } if index == *to => *from,
+            Move::Relocate { from, to } if from < to && (*from..*to).contains(&index) => index + 1,
+            Move::Relocate { from, to } if to < from && (to + 1..=*from).contains(&index) => {
+                index - 1
+            }
+            Move::Chain(moves) => moves
+                .iter()
+                .rev()
+                .fold(index, |index, step| step.source_index(index)),
+            _ => index,
+        }
+    }
+
+    // Gene at the given position after the move, without applying it
+    pub fn gene_at(&self, genes: &[Gene], index: usize) -> Gene {
+        genes[self.source_index(index)]
+    }
+
+    // Positions the move may change, every gene outside of them stays in place
+    pub fn affected_range(&self) -> Option<(usize, usize)> {
+        match self {
+            Move::Swap(a, b) | Move::TwoOpt(a, b) => Some((*a.min(b), *a.max(b))),
+            Move::Relocate { from, to } => Some((*from.min(to), *from.max(to))),
+            Move::Chain(moves) => moves
+                .iter()
+                .filter_map(|step| step.affected_range())
+                .reduce(|(start, end), (step_start, step_end)| {
+                    (start.min(step_start), end.max(step_end))
+                }),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use rand::{Rng, RngCore};
+
+use super::{FitnessCache, Individual};
+
+pub type Real = f64;
+
+// Search range of a single variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub lower: Real,
+    pub upper: Real,
+}
+
+impl Bounds {
+    pub fn new(lower: Real, upper: Real) -> Self {
+        Bounds { lower, upper }
+    }
+
+    pub fn clamp(&self, value: Real) -> Real {
+        value.clamp(self.lower, self.upper)
+    }
+}
+
+// Vector of real variables, each one kept within its bounds by the operators
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RealIndividual {
+    values: Vec<Real>,
+    fitness: FitnessCache,
+}
+
+// Replaces a single variable with the given value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Perturbation {
+    pub index: usize,
+    pub value: Real,
+}
+
+impl Individual for RealIndividual {
+    type Move = Perturbation;
+
+    fn apply_move(&mut self, step: &Perturbation) {
+        self.fitness.invalidate();
+        self.values[step.index] = step.value;
+    }
+
+    fn fitness_cache(&self) -> &FitnessCache {
+        &self.fitness
+    }
+}
+
+impl RealIndividual {
+    pub fn random(bounds: &[Bounds], rng: &mut dyn RngCore) -> Self {
+        RealIndividual::from(
+            bounds
+                .iter()
+                .map(|bounds| rng.gen_range(bounds.lower..=bounds.upper))
+                .collect::<Vec<Real>>(),
+        )
+    }
+
+    pub fn values(&self) -> &Vec<Real> {
+        &self.values
+    }
+
+    pub fn values_mut(&mut self) -> &mut Vec<Real> {
+        self.fitness.invalidate();
+        &mut self.values
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn random_value_index(&self, rng: &mut dyn RngCore) -> usize {
+        rng.gen_range(0..self.values.len())
+    }
+
+    pub fn perturb(&mut self, index: usize, sigma: Real, bounds: &Bounds, rng: &mut dyn RngCore) {
+        let step = self.random_perturbation(index, sigma, bounds, rng);
+        self.apply_move(&step);
+    }
+
+    // Adds normally distributed noise (Box-Muller) to a single value, keeping it within bounds
+    pub fn random_perturbation(
+        &self,
+        index: usize,
+        sigma: Real,
+        bounds: &Bounds,
+        rng: &mut dyn RngCore,
+    ) -> Perturbation {
+        let (u, v): (Real, Real) = (rng.gen_range(Real::EPSILON..1.0), rng.gen());
+        let noise = (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos();
+        Perturbation {
+            index,
+            value: bounds.clamp(self.values[index] + sigma * noise),
+        }
+    }
+}
+
+impl From<Vec<Real>> for RealIndividual {
+    fn from(values: Vec<Real>) -> Self {
+        RealIndividual {
+            values,
+            fitness: FitnessCache::default(),
+        }
+    }
+}
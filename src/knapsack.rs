@@ -0,0 +1,101 @@
+use crate::individual::{bitstring::BitIndividual, Fitness};
+use crate::problem::{ObjectiveSense, Problem};
+use anyhow::{anyhow, Result};
+use rand::RngCore;
+
+#[cfg(test)]
+mod tests;
+
+pub type Weight = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Item {
+    pub value: Weight,
+    pub weight: Weight,
+}
+
+// 0-1 knapsack, bit i tells whether item i is packed
+#[derive(Debug, Default)]
+pub struct KnapsackProblem {
+    items: Vec<Item>,
+    capacity: Weight,
+}
+
+impl Problem<BitIndividual> for KnapsackProblem {
+    fn random_individual(&self, rng: &mut dyn RngCore) -> BitIndividual {
+        BitIndividual::random(self.items.len(), rng)
+    }
+
+    fn sense(&self) -> ObjectiveSense {
+        ObjectiveSense::Maximize
+    }
+
+    // Overweight selections lose the value of the excess weight at the best value density,
+    // so that they are never better than a feasible one
+    fn eval(&self, individual: &BitIndividual) -> Result<Fitness> {
+        let (value, weight) = self.packed(individual)?;
+        let excess = weight.saturating_sub(self.capacity);
+        let penalty = excess as Fitness * self.max_density();
+        Ok((value as Fitness - penalty).max(0.0))
+    }
+
+    fn serialize_indiviual(&self, individual: &BitIndividual) -> String {
+        match self.packed(individual) {
+            Ok((value, weight)) => format!(
+                "Items: {}\nValue: {}\nWeight: {}\n",
+                individual
+                    .bits()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, packed)| **packed)
+                    .map(|(index, _)| (index + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                value,
+                weight
+            ),
+            Err(err) => format!("Invalid individual: {}", err),
+        }
+    }
+}
+
+impl KnapsackProblem {
+    pub fn new(items: Vec<Item>, capacity: Weight) -> Self {
+        KnapsackProblem { items, capacity }
+    }
+
+    pub fn items(&self) -> &Vec<Item> {
+        &self.items
+    }
+
+    pub fn capacity(&self) -> Weight {
+        self.capacity
+    }
+
+    // Total value and weight of the packed items
+    pub fn packed(&self, individual: &BitIndividual) -> Result<(Weight, Weight)> {
+        if individual.number_of_bits() != self.items.len() {
+            return Err(anyhow!(
+                "Selection has {} bits, but there are {} items",
+                individual.number_of_bits(),
+                self.items.len()
+            ));
+        }
+
+        Ok(self
+            .items
+            .iter()
+            .zip(individual.bits())
+            .filter(|(_, packed)| **packed)
+            .fold((0, 0), |(value, weight), (item, _)| {
+                (value + item.value, weight + item.weight)
+            }))
+    }
+
+    fn max_density(&self) -> Fitness {
+        self.items
+            .iter()
+            .map(|item| item.value as Fitness / item.weight.max(1) as Fitness)
+            .fold(0.0, Fitness::max)
+    }
+}
@@ -0,0 +1,86 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    evolutionary_algorithm::{
+        algorithm::EvolutionaryAlgorithmBuilder,
+        crossover::{CrossoverOperator, UniformCrossover},
+        mutation::BitFlipMutation,
+        selection::{Selector, TournamentSelector},
+    },
+    individual::bitstring::BitIndividual,
+    logger::NoopLogger,
+    population::Population,
+    problem::Problem,
+    solver::Solver,
+};
+
+use super::{Item, KnapsackProblem};
+
+fn small_instance() -> KnapsackProblem {
+    let items = [(60, 10), (100, 20), (120, 30), (10, 25)]
+        .iter()
+        .map(|(value, weight)| Item {
+            value: *value,
+            weight: *weight,
+        })
+        .collect();
+    KnapsackProblem::new(items, 50)
+}
+
+#[test]
+fn overweight_selections_are_penalized() {
+    let problem = small_instance();
+
+    let optimal = BitIndividual::from(vec![false, true, true, false]);
+    assert_eq!(problem.eval(&optimal).unwrap(), 220.0);
+
+    // 10 units over the capacity at the best density of 6 per unit
+    let overweight = BitIndividual::from(vec![true, true, true, false]);
+    assert_eq!(problem.eval(&overweight).unwrap(), 280.0 - 60.0);
+    assert!(problem.eval(&BitIndividual::from(vec![true])).is_err());
+}
+
+#[test]
+fn selection_prefers_higher_values_when_maximizing() {
+    let problem = small_instance();
+    let optimal = BitIndividual::from(vec![false, true, true, false]);
+    let population = Population::new(vec![
+        BitIndividual::from(vec![true, false, false, false]),
+        optimal.clone(),
+        BitIndividual::from(vec![false, false, false, true]),
+    ]);
+
+    // Every tournament contains the whole population
+    let selected = TournamentSelector::new(3)
+        .select(population, &problem, &mut StdRng::seed_from_u64(0))
+        .unwrap();
+
+    assert!(selected
+        .solutions()
+        .iter()
+        .all(|individual| *individual == optimal));
+    assert_eq!(selected.best(&problem).1, 220.0);
+}
+
+#[test]
+fn evolutionary_algorithm_on_bitstrings() {
+    let problem = small_instance();
+    let mut algorithm = EvolutionaryAlgorithmBuilder::new()
+        .population_size(20)
+        .generations(30)
+        .crossover_prob(0.7)
+        .mutation_prob(0.3)
+        .crossover_operator(CrossoverOperator::SingleChildCrossoverOperator(Box::new(
+            UniformCrossover {},
+        )))
+        .mutation_operator(Box::new(BitFlipMutation {}))
+        .selection_operator(Box::new(TournamentSelector::new(3)))
+        .logger(Box::new(NoopLogger))
+        .build()
+        .unwrap();
+
+    let (fitness, solution) = algorithm.solve(&problem).unwrap();
+
+    assert_eq!(solution.number_of_bits(), 4);
+    assert_eq!(fitness, problem.eval(&solution).unwrap());
+}
@@ -1,14 +1,24 @@
+pub mod continuous;
+pub mod evaluation;
 pub mod evolutionary_algorithm;
+pub mod flow_shop;
 pub mod greedy_algorithm;
 pub mod individual;
+pub mod knapsack;
 pub mod logger;
 pub mod neighbor;
+pub mod parsing;
+pub mod pdp;
 pub mod population;
 pub mod problem;
 pub mod problem_loader;
+pub mod qap;
 pub mod runners;
 pub mod saea_roulette;
 pub mod simulated_annealing;
 pub mod solver;
 pub mod tabu_search;
+pub mod termination;
+pub mod tsp;
 pub mod tssa;
+pub mod vrptw;
@@ -1,8 +1,11 @@
 use anyhow::Result;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
+use std::sync::Mutex;
 
-use crate::individual::Fitness;
+// Solvers running on different threads may share an output file, every flush appends
+// its rows at once so that they never interleave
+static FLUSH_LOCK: Mutex<()> = Mutex::new(());
 
 #[derive(Default)]
 pub struct CSVLogger<T> {
@@ -16,6 +19,18 @@ pub trait PersistableLogger<T> {
     fn flush(&mut self) -> Result<u64>;
 }
 
+// Drops every entry, for runs whose progress does not have to be kept, e.g. in tests
+#[derive(Debug, Default)]
+pub struct NoopLogger;
+
+impl<T> PersistableLogger<T> for NoopLogger {
+    fn log(&mut self, _entry: T) {}
+
+    fn flush(&mut self) -> Result<u64> {
+        Ok(0)
+    }
+}
+
 impl<T> CSVLogger<T> {
     pub fn new(output_filename: &str, headers: Option<Vec<String>>) -> Self {
         CSVLogger {
@@ -54,10 +69,6 @@ impl From<&CSVEntry> for String {
     }
 }
 
-pub fn inverse_fitness(fitness: Fitness) -> Fitness {
-    (1f32 / fitness) - 1f32
-}
-
 impl<T> PersistableLogger<T> for CSVLogger<T>
 where
     for<'a> &'a T: Into<CSVEntry>,
@@ -77,22 +88,23 @@ where
             .collect::<Vec<String>>()
             .join("\n");
 
+        let mut output = String::new();
+        if let Some(headers) = &self.headers {
+            output.push_str(&headers.join(";"));
+            output.push('\n');
+        }
+        output.push_str(&rows);
+        output.push('\n');
+
+        let _guard = FLUSH_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         let mut output_file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&self.output_filename)
             .unwrap();
-
-        match &self.headers {
-            Some(headers) => {
-                let headers = headers.join(";");
-                output_file.write_all(headers.as_bytes())?;
-                output_file.write_all(b"\n")?;
-            }
-            None => {}
-        }
-        output_file.write_all(rows.as_bytes())?;
-        output_file.write_all(b"\n")?;
+        output_file.write_all(output.as_bytes())?;
         Ok(self.entries.len() as u64)
     }
 }
@@ -1,7 +1,5 @@
-use std::fs::read_to_string;
-
 use evolutionary_algorithm::{
-    evolutionary_algorithm::tests::get_ea_best_three, logger::inverse_fitness, problem_loader,
+    evolutionary_algorithm::tests::get_ea_best_three, problem::Problem, problem_loader,
     runners::run_comparisons, saea_roulette::tests::get_saea_configuration,
     simulated_annealing::tests::get_simulated_annealing_best_three, solver::Solver,
     tabu_search::tests::get_tabu_search_best_three, tssa::tests::get_tssa_config,
@@ -13,7 +11,7 @@ fn run_simulated_annealing_precision(instances: &Vec<&str>) {
             "./csv/simulated-annealing-best-{}",
             instance
                 .split('/')
-                .last()
+                .next_back()
                 .into_iter()
                 .collect::<Vec<&str>>()
                 .first()
@@ -22,27 +20,33 @@ fn run_simulated_annealing_precision(instances: &Vec<&str>) {
         .unwrap()
         .iter_mut()
         .for_each(|configuration| {
-            let problem_contents = read_to_string(instance).unwrap();
-            let mut problem = problem_loader::CVRProblem::from(problem_contents);
-            problem.precalculate_distances();
+            let problem = match problem_loader::CVRProblem::from_file(instance) {
+                Ok(problem) => problem,
+                Err(err) => {
+                    println!("failed to load instance {:#}", err);
+                    return;
+                }
+            };
             match configuration.solve(&problem) {
                 Err(err) => {
                     println!("failed to solve test data {}", err)
                 }
                 Ok(val) => {
-                    println!("Solved at: {}", inverse_fitness(val.0))
+                    println!("Solved at: {}", val.0);
+                    println!("{}", problem.serialize_indiviual(&val.1));
                 }
             }
         });
     }
 }
+#[allow(dead_code)]
 fn run_simulated_tabu_seatch(instances: &Vec<&str>) {
     for instance in instances.iter() {
         get_tabu_search_best_three(&format!(
             "./csv/tabu-search-best-{}",
             instance
                 .split('/')
-                .last()
+                .next_back()
                 .into_iter()
                 .collect::<Vec<&str>>()
                 .first()
@@ -51,28 +55,34 @@ fn run_simulated_tabu_seatch(instances: &Vec<&str>) {
         .unwrap()
         .iter_mut()
         .for_each(|configuration| {
-            let problem_contents = read_to_string(instance).unwrap();
-            let mut problem = problem_loader::CVRProblem::from(problem_contents);
-            problem.precalculate_distances();
+            let problem = match problem_loader::CVRProblem::from_file(instance) {
+                Ok(problem) => problem,
+                Err(err) => {
+                    println!("failed to load instance {:#}", err);
+                    return;
+                }
+            };
             match configuration.solve(&problem) {
                 Err(err) => {
                     println!("failed to solve test data {}", err)
                 }
                 Ok(val) => {
-                    println!("Solved at: {}", inverse_fitness(val.0))
+                    println!("Solved at: {}", val.0);
+                    println!("{}", problem.serialize_indiviual(&val.1));
                 }
             }
         });
     }
 }
 
+#[allow(dead_code)]
 fn run_evolutionary_algorithm(instances: &Vec<&str>) {
     for instance in instances.iter() {
         get_ea_best_three(&format!(
             "./csv/ea-best-{}",
             instance
                 .split('/')
-                .last()
+                .next_back()
                 .into_iter()
                 .collect::<Vec<&str>>()
                 .first()
@@ -81,28 +91,34 @@ fn run_evolutionary_algorithm(instances: &Vec<&str>) {
         .unwrap()
         .iter_mut()
         .for_each(|configuration| {
-            let problem_contents = read_to_string(instance).unwrap();
-            let mut problem = problem_loader::CVRProblem::from(problem_contents);
-            problem.precalculate_distances();
+            let problem = match problem_loader::CVRProblem::from_file(instance) {
+                Ok(problem) => problem,
+                Err(err) => {
+                    println!("failed to load instance {:#}", err);
+                    return;
+                }
+            };
             match configuration.solve(&problem) {
                 Err(err) => {
                     println!("failed to solve test data {}", err)
                 }
                 Ok(val) => {
-                    println!("Solved at: {}", inverse_fitness(val.0))
+                    println!("Solved at: {}", val.0);
+                    println!("{}", problem.serialize_indiviual(&val.1));
                 }
             }
         });
     }
 }
 
+#[allow(dead_code)]
 fn run_tssa(instances: &Vec<&str>) {
     for instance in instances.iter() {
         get_tssa_config(&format!(
             "./csv/tssa-best-{}",
             instance
                 .split('/')
-                .last()
+                .next_back()
                 .into_iter()
                 .collect::<Vec<&str>>()
                 .first()
@@ -111,28 +127,34 @@ fn run_tssa(instances: &Vec<&str>) {
         .unwrap()
         .iter_mut()
         .for_each(|configuration| {
-            let problem_contents = read_to_string(instance).unwrap();
-            let mut problem = problem_loader::CVRProblem::from(problem_contents);
-            problem.precalculate_distances();
+            let problem = match problem_loader::CVRProblem::from_file(instance) {
+                Ok(problem) => problem,
+                Err(err) => {
+                    println!("failed to load instance {:#}", err);
+                    return;
+                }
+            };
             match configuration.solve(&problem) {
                 Err(err) => {
                     println!("failed to solve test data {}", err)
                 }
                 Ok(val) => {
-                    println!("Solved at: {}", inverse_fitness(val.0))
+                    println!("Solved at: {}", val.0);
+                    println!("{}", problem.serialize_indiviual(&val.1));
                 }
             }
         });
     }
 }
 
+#[allow(dead_code)]
 fn run_saea(instances: &Vec<&str>) {
     for instance in instances.iter() {
         get_saea_configuration(&format!(
             "./csv/saea-best-{}",
             instance
                 .split('/')
-                .last()
+                .next_back()
                 .into_iter()
                 .collect::<Vec<&str>>()
                 .first()
@@ -141,15 +163,20 @@ fn run_saea(instances: &Vec<&str>) {
         .unwrap()
         .iter_mut()
         .for_each(|configuration| {
-            let problem_contents = read_to_string(instance).unwrap();
-            let mut problem = problem_loader::CVRProblem::from(problem_contents);
-            problem.precalculate_distances();
+            let problem = match problem_loader::CVRProblem::from_file(instance) {
+                Ok(problem) => problem,
+                Err(err) => {
+                    println!("failed to load instance {:#}", err);
+                    return;
+                }
+            };
             match configuration.solve(&problem) {
                 Err(err) => {
                     println!("failed to solve test data {}", err)
                 }
                 Ok(val) => {
-                    println!("Solved at: {}", inverse_fitness(val.0))
+                    println!("Solved at: {}", val.0);
+                    println!("{}", problem.serialize_indiviual(&val.1));
                 }
             }
         });
@@ -1,8 +1,46 @@
-use crate::individual::VecIndividual;
+use anyhow::Result;
+use rand::RngCore;
 
-pub trait NeighborOperator {
-    fn get_neighborhood(&self, individual: &VecIndividual) -> Vec<VecIndividual>;
+use crate::evaluation::Evaluation;
+use crate::individual::{
+    bitstring::{BitFlip, BitIndividual},
+    moves::Move,
+    real::{Bounds, Perturbation, Real, RealIndividual},
+    Fitness, Individual, VecIndividual,
+};
+use crate::pdp::pairs::PickupDeliveryPairs;
+use crate::problem::Problem;
+
+pub trait NeighborOperator<I: Individual = VecIndividual> {
+    fn get_moves(&self, individual: &I, rng: &mut dyn RngCore) -> Vec<I::Move>;
     fn name(&self) -> String;
+
+    // Solvers score the moves first and only apply the chosen ones
+    fn get_neighborhood(&self, individual: &I, rng: &mut dyn RngCore) -> Vec<I> {
+        self.get_moves(individual, rng)
+            .iter()
+            .map(|step| {
+                let mut neighbor = individual.clone();
+                neighbor.apply_move(step);
+                neighbor
+            })
+            .collect()
+    }
+}
+
+// Moves with the objective value of the neighbor they lead to, best first
+pub fn scored_moves<I: Individual>(
+    problem: &dyn Problem<I>,
+    individual: &I,
+    steps: Vec<I::Move>,
+    evaluation: &Evaluation,
+) -> Result<Vec<(I::Move, Fitness)>> {
+    let values = evaluation.move_values(problem, individual, &steps)?;
+    let sense = problem.sense();
+
+    let mut scored: Vec<(I::Move, Fitness)> = steps.into_iter().zip(values).collect();
+    scored.sort_by(|(_, a), (_, b)| sense.compare(*b, *a));
+    Ok(scored)
 }
 
 pub struct SwapNeighborhoodOperator {
@@ -18,17 +56,15 @@ impl NeighborOperator for SwapNeighborhoodOperator {
     fn name(&self) -> String {
         format!("swap ({})", self.neighborhood_size)
     }
-    fn get_neighborhood(&self, individual: &VecIndividual) -> Vec<VecIndividual> {
+    fn get_moves(&self, individual: &VecIndividual, rng: &mut dyn RngCore) -> Vec<Move> {
         (0..self.neighborhood_size)
             .map(|_| {
-                let start_index = individual.random_gene_index();
-                let end_index = individual.random_gene_index();
-
-                let mut neighbor = individual.clone();
-                neighbor.genes_mut().swap(start_index, end_index);
-                neighbor
+                Move::Swap(
+                    individual.random_gene_index(rng),
+                    individual.random_gene_index(rng),
+                )
             })
-            .collect::<Vec<VecIndividual>>()
+            .collect::<Vec<Move>>()
     }
 }
 
@@ -47,16 +83,144 @@ impl NeighborOperator for InverseNeighborhoodOperator {
         format!("inverse ({})", self.neighborhood_size)
     }
 
-    fn get_neighborhood(&self, individual: &VecIndividual) -> Vec<VecIndividual> {
+    fn get_moves(&self, individual: &VecIndividual, rng: &mut dyn RngCore) -> Vec<Move> {
         (0..self.neighborhood_size)
             .map(|_| {
-                let (start_index, end_index) = individual.random_gene_range_indexes();
-                let mut neighbor = individual.clone();
-                for index in start_index..(start_index + end_index) / 2 {
-                    neighbor.genes_mut().swap(index, end_index - index);
-                }
-                neighbor
+                let (start_index, end_index) = individual.random_gene_range_indexes(rng);
+                Move::TwoOpt(start_index, end_index)
+            })
+            .collect::<Vec<Move>>()
+    }
+}
+
+pub struct RelocateNeighborhoodOperator {
+    neighborhood_size: u16,
+}
+
+impl RelocateNeighborhoodOperator {
+    pub fn new(neighborhood_size: u16) -> Self {
+        RelocateNeighborhoodOperator { neighborhood_size }
+    }
+}
+
+impl NeighborOperator for RelocateNeighborhoodOperator {
+    fn name(&self) -> String {
+        format!("relocate ({})", self.neighborhood_size)
+    }
+
+    fn get_moves(&self, individual: &VecIndividual, rng: &mut dyn RngCore) -> Vec<Move> {
+        (0..self.neighborhood_size)
+            .map(|_| Move::Relocate {
+                from: individual.random_gene_index(rng),
+                to: individual.random_gene_index(rng),
+            })
+            .collect::<Vec<Move>>()
+    }
+}
+
+// Precedence preserving neighborhoods for pickup and delivery problems
+pub struct PairSwapNeighborhoodOperator {
+    neighborhood_size: u16,
+    pairs: PickupDeliveryPairs,
+}
+
+impl PairSwapNeighborhoodOperator {
+    pub fn new(neighborhood_size: u16, pairs: PickupDeliveryPairs) -> Self {
+        PairSwapNeighborhoodOperator {
+            neighborhood_size,
+            pairs,
+        }
+    }
+}
+
+impl NeighborOperator for PairSwapNeighborhoodOperator {
+    fn name(&self) -> String {
+        format!("pair swap ({})", self.neighborhood_size)
+    }
+
+    fn get_moves(&self, individual: &VecIndividual, rng: &mut dyn RngCore) -> Vec<Move> {
+        (0..self.neighborhood_size)
+            .filter_map(|_| self.pairs.random_pairs_swap(individual.genes(), rng))
+            .collect::<Vec<Move>>()
+    }
+}
+
+pub struct PairRelocateNeighborhoodOperator {
+    neighborhood_size: u16,
+    pairs: PickupDeliveryPairs,
+}
+
+impl PairRelocateNeighborhoodOperator {
+    pub fn new(neighborhood_size: u16, pairs: PickupDeliveryPairs) -> Self {
+        PairRelocateNeighborhoodOperator {
+            neighborhood_size,
+            pairs,
+        }
+    }
+}
+
+impl NeighborOperator for PairRelocateNeighborhoodOperator {
+    fn name(&self) -> String {
+        format!("pair relocate ({})", self.neighborhood_size)
+    }
+
+    fn get_moves(&self, individual: &VecIndividual, rng: &mut dyn RngCore) -> Vec<Move> {
+        (0..self.neighborhood_size)
+            .filter_map(|_| self.pairs.random_pair_relocation(individual.genes(), rng))
+            .collect::<Vec<Move>>()
+    }
+}
+
+pub struct BitFlipNeighborhoodOperator {
+    neighborhood_size: u16,
+}
+
+impl BitFlipNeighborhoodOperator {
+    pub fn new(neighborhood_size: u16) -> Self {
+        BitFlipNeighborhoodOperator { neighborhood_size }
+    }
+}
+
+impl NeighborOperator<BitIndividual> for BitFlipNeighborhoodOperator {
+    fn name(&self) -> String {
+        format!("bit flip ({})", self.neighborhood_size)
+    }
+
+    fn get_moves(&self, individual: &BitIndividual, rng: &mut dyn RngCore) -> Vec<BitFlip> {
+        (0..self.neighborhood_size)
+            .map(|_| BitFlip(individual.random_bit_index(rng)))
+            .collect::<Vec<BitFlip>>()
+    }
+}
+
+// Neighbors differ by gaussian noise on one variable, bounds are given per variable
+pub struct GaussianNeighborhoodOperator {
+    neighborhood_size: u16,
+    sigma: Real,
+    bounds: Vec<Bounds>,
+}
+
+impl GaussianNeighborhoodOperator {
+    pub fn new(neighborhood_size: u16, sigma: Real, bounds: Vec<Bounds>) -> Self {
+        GaussianNeighborhoodOperator {
+            neighborhood_size,
+            sigma,
+            bounds,
+        }
+    }
+}
+
+impl NeighborOperator<RealIndividual> for GaussianNeighborhoodOperator {
+    fn name(&self) -> String {
+        format!("gaussian {} ({})", self.sigma, self.neighborhood_size)
+    }
+
+    fn get_moves(&self, individual: &RealIndividual, rng: &mut dyn RngCore) -> Vec<Perturbation> {
+        (0..self.neighborhood_size)
+            .map(|_| {
+                let index = individual.random_value_index(rng);
+                individual.random_perturbation(index, self.sigma, &self.bounds[index], rng)
             })
-            .collect::<Vec<VecIndividual>>()
+            .collect::<Vec<Perturbation>>()
     }
 }
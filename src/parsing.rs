@@ -0,0 +1,10 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+// Shared by the parsers of the instance formats read line by line
+pub(crate) fn parse_number<T: FromStr>(value: &str, line_number: usize) -> Result<T> {
+    value
+        .parse::<T>()
+        .map_err(|_| anyhow!("line {}: invalid number '{}'", line_number, value))
+}
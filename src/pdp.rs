@@ -0,0 +1,211 @@
+use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::problem::Problem;
+use crate::problem_loader::{
+    distance::{distance_matrix, DistanceFunction, DistancePrecision},
+    fleet::FleetLimit,
+    solution_file::SolutionFile,
+    Coordinates, Demand,
+};
+use crate::vrptw::{Time, TimeWindows};
+use anyhow::{anyhow, Context, Result};
+use pairs::PickupDeliveryPairs;
+use rand::{Rng, RngCore};
+use std::fs::read_to_string;
+use std::path::Path;
+
+pub mod pairs;
+pub mod parser;
+pub mod schedule;
+#[cfg(test)]
+mod tests;
+
+// Positive for pickups, negative for deliveries
+pub type Load = i32;
+
+// Node of a Li & Lim instance, the depot is the task number 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub coordinates: Coordinates,
+    pub load: Load,
+    pub ready_time: Time,
+    pub due_date: Time,
+    pub service_time: Time,
+    // Pickup of a delivery task
+    pub pickup: Option<Gene>,
+    // Delivery of a pickup task
+    pub delivery: Option<Gene>,
+}
+
+#[derive(Debug, Default)]
+pub struct PDProblem {
+    name: String,
+    vehicles: usize,
+    capacity: Demand,
+    // Task numbers are used as genes, so the depot is always at index 0
+    tasks: Vec<Task>,
+    pairs: PickupDeliveryPairs,
+    distances: Vec<Vec<Fitness>>,
+    time_windows: TimeWindows,
+    fleet_limit: FleetLimit,
+}
+
+impl Problem for PDProblem {
+    // Inserts every request at random positions, keeping the pickup before its delivery
+    fn random_individual(&self, rng: &mut dyn RngCore) -> VecIndividual {
+        let mut individual = VecIndividual::new();
+        for (pickup, delivery) in self.pairs.pairs() {
+            let genes = individual.genes_mut();
+            let pickup_index = rng.gen::<usize>() % (genes.len() + 1);
+            genes.insert(pickup_index, *pickup);
+            let delivery_index =
+                pickup_index + 1 + rng.gen::<usize>() % (genes.len() - pickup_index);
+            genes.insert(delivery_index, *delivery);
+        }
+        individual
+    }
+
+    fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
+        self.total_cost(individual)
+    }
+
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        self.solution_file(individual)
+            .map(|solution| solution.to_string())
+            .unwrap_or_else(|err| format!("Invalid individual: {}", err))
+    }
+}
+
+impl PDProblem {
+    pub fn new(
+        name: String,
+        vehicles: usize,
+        capacity: Demand,
+        tasks: Vec<Task>,
+    ) -> Result<PDProblem> {
+        let pairs = PickupDeliveryPairs::from_tasks(&tasks)?;
+        let mut problem = PDProblem {
+            name,
+            vehicles,
+            capacity,
+            tasks,
+            pairs,
+            ..PDProblem::default()
+        };
+        problem.precalculate_distances();
+        Ok(problem)
+    }
+
+    pub fn from_file(path: &str) -> Result<PDProblem> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let mut problem =
+            PDProblem::parse(&content).with_context(|| format!("Failed to parse {}", path))?;
+
+        // Li & Lim files do not contain the instance name
+        problem.name = Path::new(path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(problem)
+    }
+
+    // Tours breaking a precedence or overloading a vehicle are infeasible and get the worst cost,
+    // late arrivals are handled according to the time windows mode
+    pub fn total_cost(&self, individual: &VecIndividual) -> Result<Fitness> {
+        if !self.pairs.is_feasible(individual.genes()) {
+            return Ok(Fitness::INFINITY);
+        }
+
+        let routes = self.decode(individual)?;
+        if routes
+            .iter()
+            .any(|route| route.max_load > self.capacity as Load)
+        {
+            return Ok(Fitness::INFINITY);
+        }
+
+        let distance: Fitness = routes.iter().map(|route| route.length).sum();
+        let lateness: Time = routes.iter().map(|route| route.lateness).sum();
+        let lateness_cost = match self.time_windows {
+            TimeWindows::Hard if lateness > 0.0 => Fitness::INFINITY,
+            TimeWindows::Hard => Fitness::default(),
+            TimeWindows::Penalized { lateness_penalty } => lateness * lateness_penalty,
+        };
+
+        Ok(distance + lateness_cost + self.fleet_limit.penalty(routes.len()))
+    }
+
+    pub fn solution_file(&self, individual: &VecIndividual) -> Result<SolutionFile> {
+        let routes = self.decode(individual)?;
+
+        Ok(SolutionFile {
+            vehicles: Some(routes.len()),
+            cost: Some(routes.iter().map(|route| route.length).sum()),
+            routes: routes
+                .into_iter()
+                .map(|route| route.stops.iter().map(|stop| *stop as usize).collect())
+                .collect(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Number of vehicles available in the instance file
+    pub fn vehicles(&self) -> usize {
+        self.vehicles
+    }
+
+    pub fn capacity(&self) -> Demand {
+        self.capacity
+    }
+
+    pub fn tasks(&self) -> &Vec<Task> {
+        &self.tasks
+    }
+
+    // Pickup and delivery pairs, needed by the precedence preserving operators
+    pub fn pairs(&self) -> &PickupDeliveryPairs {
+        &self.pairs
+    }
+
+    pub fn time_windows(&self) -> TimeWindows {
+        self.time_windows
+    }
+
+    pub fn set_time_windows(&mut self, time_windows: TimeWindows) {
+        self.time_windows = time_windows;
+    }
+
+    pub fn fleet_limit(&self) -> FleetLimit {
+        self.fleet_limit
+    }
+
+    pub fn set_fleet_limit(&mut self, fleet_limit: FleetLimit) {
+        self.fleet_limit = fleet_limit;
+    }
+
+    pub fn task(&self, node: &Gene) -> Result<&Task> {
+        self.tasks
+            .get(*node as usize)
+            .with_context(|| format!("Task {} does not exist", node))
+    }
+
+    // Travel times are equal to the distances, as in the Li & Lim benchmark
+    pub fn distance(&self, node_a: &Gene, node_b: &Gene) -> Result<Fitness> {
+        self.distances
+            .get(*node_a as usize)
+            .and_then(|row| row.get(*node_b as usize))
+            .copied()
+            .ok_or(anyhow!("Failed to get distance, invalid node indexes"))
+    }
+
+    fn precalculate_distances(&mut self) {
+        let nodes: Vec<Coordinates> = self.tasks.iter().map(|node| node.coordinates).collect();
+        self.distances = distance_matrix(
+            DistanceFunction::Euclidean2D,
+            &nodes,
+            DistancePrecision::Exact,
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+use rand::{Rng, RngCore};
+
+use crate::individual::{moves::Move, Gene, Individual, VecIndividual};
+
+use super::Task;
+
+// Pickup and delivery genes of every request, shared by the precedence preserving operators
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PickupDeliveryPairs {
+    pairs: Vec<(Gene, Gene)>,
+}
+
+impl PickupDeliveryPairs {
+    pub fn new(pairs: Vec<(Gene, Gene)>) -> Self {
+        PickupDeliveryPairs { pairs }
+    }
+
+    // Every task except the depot has to belong to exactly one pair pointing back at it
+    pub fn from_tasks(tasks: &[Task]) -> Result<Self> {
+        let mut pairs = Vec::new();
+
+        for (index, task) in tasks.iter().enumerate().skip(1) {
+            let gene = index as Gene;
+            match (task.pickup, task.delivery) {
+                (None, Some(delivery)) => {
+                    let delivery_task = tasks.get(delivery as usize).ok_or(anyhow!(
+                        "Delivery {} of task {} does not exist",
+                        delivery,
+                        gene
+                    ))?;
+                    if delivery_task.pickup != Some(gene) {
+                        return Err(anyhow!(
+                            "Delivery {} does not point back at pickup {}",
+                            delivery,
+                            gene
+                        ));
+                    }
+                    pairs.push((gene, delivery));
+                }
+                (Some(_), None) => {}
+                _ => {
+                    return Err(anyhow!(
+                        "Task {} has to be either a pickup or a delivery",
+                        gene
+                    ))
+                }
+            }
+        }
+
+        if pairs.len() * 2 + 1 != tasks.len() {
+            return Err(anyhow!("Some deliveries have no matching pickup"));
+        }
+
+        Ok(PickupDeliveryPairs { pairs })
+    }
+
+    pub fn pairs(&self) -> &Vec<(Gene, Gene)> {
+        &self.pairs
+    }
+
+    // Every pickup is visited before its delivery
+    pub fn is_feasible(&self, genes: &[Gene]) -> bool {
+        let positions = positions(genes);
+        self.pairs.iter().all(|(pickup, delivery)| {
+            match (
+                positions.get(*pickup as usize),
+                positions.get(*delivery as usize),
+            ) {
+                (Some(Some(pickup)), Some(Some(delivery))) => pickup < delivery,
+                _ => false,
+            }
+        })
+    }
+
+    // Moves a random request to random positions, still visiting the pickup first
+    pub fn relocate_random_pair(&self, individual: &mut VecIndividual, rng: &mut dyn RngCore) {
+        if let Some(step) = self.random_pair_relocation(individual.genes(), rng) {
+            individual.apply_move(&step);
+        }
+    }
+
+    // Exchanges the positions of two requests, pickups with pickups and deliveries with deliveries,
+    // which keeps both precedences intact
+    pub fn swap_random_pairs(&self, individual: &mut VecIndividual, rng: &mut dyn RngCore) {
+        if let Some(step) = self.random_pairs_swap(individual.genes(), rng) {
+            individual.apply_move(&step);
+        }
+    }
+
+    // The delivery goes to the end first, so the pickup can be inserted among the other genes
+    // and the delivery brought back anywhere after it
+    pub fn random_pair_relocation(&self, genes: &[Gene], rng: &mut dyn RngCore) -> Option<Move> {
+        let (pickup, delivery) = self.random_pair(rng)?;
+        let pickup_position = genes.iter().position(|gene| *gene == pickup)?;
+        let delivery_position = genes.iter().position(|gene| *gene == delivery)?;
+        let last = genes.len() - 1;
+
+        let pickup_index = rng.gen::<usize>() % last;
+        let delivery_index = pickup_index + 1 + rng.gen::<usize>() % (last - pickup_index);
+        // Removing the delivery shifts the pickup placed after it
+        let pickup_position = match pickup_position > delivery_position {
+            true => pickup_position - 1,
+            false => pickup_position,
+        };
+
+        Some(Move::Chain(vec![
+            Move::Relocate {
+                from: delivery_position,
+                to: last,
+            },
+            Move::Relocate {
+                from: pickup_position,
+                to: pickup_index,
+            },
+            Move::Relocate {
+                from: last,
+                to: delivery_index,
+            },
+        ]))
+    }
+
+    pub fn random_pairs_swap(&self, genes: &[Gene], rng: &mut dyn RngCore) -> Option<Move> {
+        let (first, second) = (self.random_pair(rng)?, self.random_pair(rng)?);
+        let mut swaps = Vec::new();
+
+        for (a, b) in [(first.0, second.0), (first.1, second.1)] {
+            let positions = (
+                genes.iter().position(|gene| *gene == a),
+                genes.iter().position(|gene| *gene == b),
+            );
+            if let (Some(a), Some(b)) = positions {
+                swaps.push(Move::Swap(a, b));
+            }
+        }
+        Some(Move::Chain(swaps))
+    }
+
+    fn random_pair(&self, rng: &mut dyn RngCore) -> Option<(Gene, Gene)> {
+        match self.pairs.len() {
+            0 => None,
+            pairs => Some(self.pairs[rng.gen::<usize>() % pairs]),
+        }
+    }
+}
+
+fn positions(genes: &[Gene]) -> Vec<Option<usize>> {
+    let mut positions = vec![None; genes.iter().max().map_or(0, |max| *max as usize + 1)];
+    for (index, gene) in genes.iter().enumerate() {
+        positions[*gene as usize] = Some(index);
+    }
+    positions
+}
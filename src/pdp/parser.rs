@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+
+use crate::individual::Gene;
+use crate::parsing::parse_number;
+use crate::problem_loader::Coordinates;
+
+use super::{PDProblem, Task};
+
+impl PDProblem {
+    // Reads Li & Lim instances: a "vehicles capacity speed" header followed by rows
+    // "task x y demand ready due service pickup delivery", starting with the depot.
+    // Pickups have a delivery index and deliveries a pickup index, the other one is 0.
+    pub fn parse(content: &str) -> Result<PDProblem> {
+        let mut rows = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (header_line, header) = rows.next().ok_or(anyhow!("empty instance"))?;
+        let (vehicles, capacity) = match header.split_whitespace().collect::<Vec<&str>>()[..] {
+            [vehicles, capacity, _speed] => (
+                parse_number(vehicles, header_line)?,
+                parse_number(capacity, header_line)?,
+            ),
+            _ => {
+                return Err(anyhow!(
+                    "line {}: malformed header '{}'",
+                    header_line,
+                    header
+                ))
+            }
+        };
+
+        let mut tasks = Vec::new();
+        for (line_number, line) in rows {
+            let [number, x, y, load, ready_time, due_date, service_time, pickup, delivery] =
+                line.split_whitespace().collect::<Vec<&str>>()[..]
+            else {
+                return Err(anyhow!("line {}: malformed row '{}'", line_number, line));
+            };
+            if parse_number::<usize>(number, line_number)? != tasks.len() {
+                return Err(anyhow!(
+                    "line {}: expected task {}, found {}",
+                    line_number,
+                    tasks.len(),
+                    number
+                ));
+            }
+
+            let pickup = parse_number::<Gene>(pickup, line_number)?;
+            let delivery = parse_number::<Gene>(delivery, line_number)?;
+            tasks.push(Task {
+                coordinates: Coordinates {
+                    x: parse_number(x, line_number)?,
+                    y: parse_number(y, line_number)?,
+                },
+                load: parse_number(load, line_number)?,
+                ready_time: parse_number(ready_time, line_number)?,
+                due_date: parse_number(due_date, line_number)?,
+                service_time: parse_number(service_time, line_number)?,
+                pickup: (pickup != 0).then_some(pickup),
+                delivery: (delivery != 0).then_some(delivery),
+            });
+        }
+
+        PDProblem::new(String::new(), vehicles, capacity, tasks)
+    }
+}
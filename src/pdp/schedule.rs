@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::vrptw::{Time, TimeWindows};
+
+use super::{Load, PDProblem};
+
+// Single vehicle trip, starting and ending at the depot
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickupDeliveryRoute {
+    pub stops: Vec<Gene>,
+    pub length: Fitness,
+    // Highest load carried between two stops
+    pub max_load: Load,
+    // Sum of arrivals after the due dates, including the return to the depot
+    pub lateness: Time,
+}
+
+impl PDProblem {
+    // The tour is cut into blocks after which no request is in progress, so that each pair
+    // stays on one vehicle and every block starts empty. With hard time windows a new route
+    // starts whenever the next block would be late, with penalized ones whenever extending the
+    // route costs more, lateness included, than serving the block by another vehicle.
+    pub fn decode(&self, individual: &VecIndividual) -> Result<Vec<PickupDeliveryRoute>> {
+        let mut routes: Vec<Vec<Gene>> = Vec::new();
+
+        for block in self.blocks(individual.genes())? {
+            let extended = match (routes.last(), self.time_windows) {
+                (Some(route), TimeWindows::Hard) => {
+                    self.schedule([route.as_slice(), &block].concat())?.lateness <= 0.0
+                }
+                (Some(route), TimeWindows::Penalized { lateness_penalty }) => {
+                    let cost = |route: PickupDeliveryRoute| {
+                        route.length + route.lateness * lateness_penalty
+                    };
+                    cost(self.schedule([route.as_slice(), &block].concat())?)
+                        <= cost(self.schedule(route.clone())?) + cost(self.schedule(block.clone())?)
+                }
+                (None, _) => false,
+            };
+
+            match routes.last_mut() {
+                Some(route) if extended => route.extend(block),
+                _ => routes.push(block),
+            }
+        }
+
+        routes
+            .into_iter()
+            .map(|stops| self.schedule(stops))
+            .collect()
+    }
+
+    fn blocks(&self, genes: &[Gene]) -> Result<Vec<Vec<Gene>>> {
+        let mut blocks = Vec::new();
+        let mut block = Vec::new();
+        let mut open_pickups = HashSet::new();
+
+        for gene in genes {
+            match self.task(gene)?.pickup {
+                Some(pickup) => open_pickups.remove(&pickup),
+                None => open_pickups.insert(*gene),
+            };
+            block.push(*gene);
+
+            if open_pickups.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        }
+
+        // Deliveries visited before their pickups leave the last block open
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    // Vehicles leave the depot at its ready time and wait for windows that are not open yet
+    pub fn schedule(&self, stops: Vec<Gene>) -> Result<PickupDeliveryRoute> {
+        let mut route = PickupDeliveryRoute {
+            stops: Vec::new(),
+            length: Fitness::default(),
+            max_load: 0,
+            lateness: Time::default(),
+        };
+        let mut time = self.tasks[0].ready_time;
+        let mut load: Load = 0;
+        let mut previous: Gene = 0;
+
+        for stop in stops.iter().chain([0].iter()) {
+            let task = self.task(stop)?;
+            let arrival = time + self.distance(&previous, stop)?;
+
+            route.length += self.distance(&previous, stop)?;
+            route.lateness += (arrival - task.due_date).max(0.0);
+            if *stop == 0 {
+                break;
+            }
+
+            load += task.load;
+            route.max_load = route.max_load.max(load);
+            time = arrival.max(task.ready_time) + task.service_time;
+            previous = *stop;
+        }
+
+        route.stops = stops;
+        Ok(route)
+    }
+}
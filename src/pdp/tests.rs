@@ -0,0 +1,101 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    evolutionary_algorithm::mutation::{Mutation, PairRelocateMutation},
+    individual::{Fitness, VecIndividual},
+    neighbor::{NeighborOperator, PairSwapNeighborhoodOperator},
+    problem::Problem,
+    vrptw::TimeWindows,
+};
+
+use super::PDProblem;
+
+// Requests 1 -> 2 and 3 -> 4, the second one can only be served within its window by its own vehicle
+const LI_LIM_INSTANCE: &str = "2 20 1
+0 0 0 0 0 1000 0 0 0
+1 10 0 10 0 1000 0 0 2
+2 20 0 -10 0 1000 0 1 0
+3 0 10 15 0 1000 0 0 4
+4 0 20 -15 0 40 0 3 0
+";
+
+#[test]
+fn parse_li_lim_instance() {
+    let problem = PDProblem::parse(LI_LIM_INSTANCE).unwrap();
+
+    assert_eq!(problem.vehicles(), 2);
+    assert_eq!(problem.capacity(), 20);
+    assert_eq!(problem.pairs().pairs(), &vec![(1, 2), (3, 4)]);
+    assert_eq!(problem.tasks()[2].load, -10);
+
+    let error = PDProblem::parse("2 20 1\n0 0 0 0 0 1000 0 0 0\n1 10 0 10 0 1000 0 0 5\n");
+    assert!(error.is_err());
+}
+
+#[test]
+fn precedence_and_capacity_are_enforced() {
+    let problem = PDProblem::parse(LI_LIM_INSTANCE).unwrap();
+
+    let feasible = VecIndividual::from(vec![3, 4, 1, 2]);
+    assert!(problem.total_cost(&feasible).unwrap() < Fitness::INFINITY);
+
+    // Delivery before its pickup
+    let reversed = VecIndividual::from(vec![2, 1, 3, 4]);
+    assert_eq!(problem.total_cost(&reversed).unwrap(), Fitness::INFINITY);
+
+    // Both requests on board at the same time need a capacity of 25
+    let overloaded = VecIndividual::from(vec![3, 1, 2, 4]);
+    assert_eq!(problem.total_cost(&overloaded).unwrap(), Fitness::INFINITY);
+}
+
+#[test]
+fn requests_stay_on_one_route() {
+    let problem = PDProblem::parse(LI_LIM_INSTANCE).unwrap();
+
+    let routes = problem
+        .decode(&VecIndividual::from(vec![3, 4, 1, 2]))
+        .unwrap();
+    let stops: Vec<Vec<u16>> = routes.iter().map(|route| route.stops.clone()).collect();
+    assert_eq!(stops, vec![vec![3, 4, 1, 2]]);
+
+    // Serving request 1 -> 2 first makes the delivery 4 late, so it gets another vehicle
+    let routes = problem
+        .decode(&VecIndividual::from(vec![1, 2, 3, 4]))
+        .unwrap();
+    let stops: Vec<Vec<u16>> = routes.iter().map(|route| route.stops.clone()).collect();
+    assert_eq!(stops, vec![vec![1, 2], vec![3, 4]]);
+}
+
+#[test]
+fn penalized_lateness_opens_new_routes() {
+    let mut problem = PDProblem::parse(LI_LIM_INSTANCE).unwrap();
+    let individual = VecIndividual::from(vec![1, 2, 3, 4]);
+
+    // One vehicle saves about 7.6 distance units but arrives about 12.4 units late at 4
+    for (lateness_penalty, routes) in [(0.1, 1), (1.0, 2)] {
+        problem.set_time_windows(TimeWindows::Penalized { lateness_penalty });
+        assert_eq!(problem.decode(&individual).unwrap().len(), routes);
+        assert!(problem.total_cost(&individual).unwrap() <= 80.0);
+    }
+}
+
+#[test]
+fn pair_operators_preserve_precedence() {
+    let problem = PDProblem::parse(LI_LIM_INSTANCE).unwrap();
+    let neighborhood = PairSwapNeighborhoodOperator::new(20, problem.pairs().clone());
+    let mutation = PairRelocateMutation::new(problem.pairs().clone());
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..20 {
+        let mut individual = problem.random_individual(&mut rng);
+        assert!(problem.pairs().is_feasible(individual.genes()));
+
+        mutation.mutate(&mut individual, &mut rng);
+        assert!(problem.pairs().is_feasible(individual.genes()));
+        assert_eq!(individual.number_of_genes(), 4);
+
+        for neighbor in neighborhood.get_neighborhood(&individual, &mut rng) {
+            assert!(problem.pairs().is_feasible(neighbor.genes()));
+        }
+    }
+}
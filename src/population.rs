@@ -1,24 +1,36 @@
-use rand::{thread_rng, RngCore};
+use anyhow::Result;
+use rand::RngCore;
 
 use crate::{
-    individual::{Fitness, VecIndividual},
+    evaluation::Evaluation,
+    individual::{Fitness, Individual, VecIndividual},
     problem::Problem,
 };
 
-#[derive(Default)]
-pub struct Population {
-    individuals: Vec<VecIndividual>,
+#[cfg(test)]
+mod tests;
+
+pub struct Population<I = VecIndividual> {
+    individuals: Vec<I>,
 }
 
-impl Population {
-    pub fn new(individuals: Vec<VecIndividual>) -> Self {
+impl<I> Default for Population<I> {
+    fn default() -> Self {
+        Population {
+            individuals: Vec::new(),
+        }
+    }
+}
+
+impl<I: Individual> Population<I> {
+    pub fn new(individuals: Vec<I>) -> Self {
         Population { individuals }
     }
-    pub fn solutions(&self) -> &Vec<VecIndividual> {
+    pub fn solutions(&self) -> &Vec<I> {
         &self.individuals
     }
 
-    pub fn mut_solutions(&mut self) -> &mut Vec<VecIndividual> {
+    pub fn mut_solutions(&mut self) -> &mut Vec<I> {
         &mut self.individuals
     }
 
@@ -26,85 +38,75 @@ impl Population {
         self.individuals.len()
     }
 
-    pub fn into_solutions(self) -> Vec<VecIndividual> {
+    pub fn into_solutions(self) -> Vec<I> {
         self.individuals
     }
-    pub fn add_individual(&mut self, individual: VecIndividual) {
+    pub fn add_individual(&mut self, individual: I) {
         self.individuals.push(individual)
     }
 
-    pub fn random_individual_index(&self) -> usize {
-        let mut rng = thread_rng();
+    pub fn random_individual_index(&self, rng: &mut dyn RngCore) -> usize {
         rng.next_u32() as usize % self.number_of_solutions()
     }
 
-    pub fn replace_subpopulation(&mut self, mut population: Population) {
+    pub fn replace_subpopulation(&mut self, mut population: Population<I>) {
         self.individuals
             .drain(0..(std::cmp::min(population.number_of_solutions(), self.individuals.len())));
         self.individuals.append(&mut population.individuals);
     }
 
-    pub fn random_individual(&self) -> &VecIndividual {
+    pub fn random_individual(&self, rng: &mut dyn RngCore) -> &I {
         self.individuals
-            .get(self.random_individual_index())
+            .get(self.random_individual_index(rng))
             .expect("random indvidual index was out of bounds")
     }
 
-    pub fn individual_with_highest_fitness<'a>(
-        problem: &dyn Problem,
-        individuals: &'a [VecIndividual],
-    ) -> (&'a VecIndividual, Fitness) {
-        let solution = individuals
+    // Fills the fitness cache of every individual, the other methods only read it afterwards
+    pub fn evaluate(&self, problem: &dyn Problem<I>, evaluation: &Evaluation) -> Result<()> {
+        evaluation.fitness(problem, self.solutions())?;
+        Ok(())
+    }
+
+    // Best individual according to the objective sense of the problem
+    pub fn best_of<'a>(problem: &dyn Problem<I>, individuals: &'a [I]) -> (&'a I, Fitness) {
+        let sense = problem.sense();
+        individuals
             .iter()
-            .max_by(|solution_a, solution_b| {
-                let eval_a = problem
-                    .eval(solution_a)
-                    .expect("VecIndividual should be valid for the problem");
-                let eval_b = problem
-                    .eval(solution_b)
-                    .expect("VecIndividual should be valid for the problem");
-
-                eval_a
-                    .partial_cmp(&eval_b)
-                    .expect("VecIndividual evaluations should be comparable")
+            .map(|individual| {
+                let fitness = individual
+                    .fitness(problem)
+                    .expect("Individual should be valid for the problem");
+                (individual, fitness)
             })
-            .unwrap();
-
-        (solution, problem.eval(solution).unwrap())
+            .max_by(|(_, a), (_, b)| sense.compare(*a, *b))
+            .expect("Population should not be empty")
     }
 
-    pub fn highest_fitness(&self, problem: &dyn Problem) -> (&VecIndividual, Fitness) {
-        Population::individual_with_highest_fitness(problem, self.solutions())
+    pub fn best(&self, problem: &dyn Problem<I>) -> (&I, Fitness) {
+        Population::best_of(problem, self.solutions())
     }
 
-    pub fn lowest_fitness(&self, problem: &dyn Problem) -> (&VecIndividual, Fitness) {
-        let solution = self
-            .solutions()
+    pub fn worst(&self, problem: &dyn Problem<I>) -> (&I, Fitness) {
+        let sense = problem.sense();
+        self.solutions()
             .iter()
-            .min_by(|solution_a, solution_b| {
-                let eval_a = problem
-                    .eval(solution_a)
-                    .expect("VecIndividual should be valid for the problem");
-                let eval_b = problem
-                    .eval(solution_b)
-                    .expect("VecIndividual should be valid for the problem");
-
-                eval_a
-                    .partial_cmp(&eval_b)
-                    .expect("VecIndividual evaluations should be comparable")
+            .map(|individual| {
+                let fitness = individual
+                    .fitness(problem)
+                    .expect("Individual should be valid for the problem");
+                (individual, fitness)
             })
-            .unwrap();
-
-        (solution, problem.eval(solution).unwrap())
+            .min_by(|(_, a), (_, b)| sense.compare(*a, *b))
+            .expect("Population should not be empty")
     }
 
-    pub fn average_fitness(&self, problem: &dyn Problem) -> Fitness {
+    pub fn average_fitness(&self, problem: &dyn Problem<I>) -> Fitness {
         let solution: Fitness = self
             .solutions()
             .iter()
-            .map(|sol| problem.eval(sol).unwrap())
+            .map(|sol| sol.fitness(problem).unwrap())
             .sum::<Fitness>();
 
-        solution / self.number_of_solutions() as f32
+        solution / self.number_of_solutions() as Fitness
     }
 }
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use rand::RngCore;
+
+use crate::{
+    individual::{Fitness, Individual, VecIndividual},
+    problem::Problem,
+};
+
+use super::Population;
+
+// Sum of the genes, counting how many times it was evaluated
+#[derive(Default)]
+struct CountingProblem {
+    evaluations: AtomicUsize,
+}
+
+impl Problem for CountingProblem {
+    fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        Ok(individual.genes().iter().map(|gene| *gene as Fitness).sum())
+    }
+
+    fn random_individual(&self, _rng: &mut dyn RngCore) -> VecIndividual {
+        VecIndividual::from(vec![1, 2, 3])
+    }
+
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        format!("{:?}", individual.genes())
+    }
+}
+
+#[test]
+fn every_individual_is_evaluated_once() {
+    let problem = CountingProblem::default();
+    let population = Population::new(vec![
+        VecIndividual::from(vec![1, 2]),
+        VecIndividual::from(vec![0, 1]),
+        VecIndividual::from(vec![3, 4]),
+    ]);
+
+    assert_eq!(population.best(&problem).1, 1.0);
+    assert_eq!(population.worst(&problem).1, 7.0);
+    assert_eq!(population.average_fitness(&problem), 11.0 / 3.0);
+    assert_eq!(problem.evaluations.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn mutation_invalidates_cached_fitness() {
+    let problem = CountingProblem::default();
+    let mut individual = VecIndividual::from(vec![1, 2]);
+    assert_eq!(individual.fitness(&problem).unwrap(), 3.0);
+
+    let clone = individual.clone();
+    assert_eq!(clone.fitness(&problem).unwrap(), 3.0);
+    assert_eq!(problem.evaluations.load(Ordering::Relaxed), 1);
+
+    individual.genes_mut()[0] = 5;
+    assert_eq!(individual.fitness(&problem).unwrap(), 7.0);
+    assert_eq!(problem.evaluations.load(Ordering::Relaxed), 2);
+    assert_ne!(individual, clone);
+}
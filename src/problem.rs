@@ -1,8 +1,72 @@
-use crate::individual::{Fitness, VecIndividual};
+use std::cmp::Ordering;
+
+use crate::individual::{Fitness, Individual, VecIndividual};
 use anyhow::Result;
+use rand::RngCore;
+
+// Whether lower or higher objective values are better
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ObjectiveSense {
+    #[default]
+    Minimize,
+    Maximize,
+}
+
+impl ObjectiveSense {
+    // Orders the values by quality, Greater when a is better than b
+    pub fn compare(&self, a: Fitness, b: Fitness) -> Ordering {
+        match self {
+            ObjectiveSense::Minimize => b.total_cmp(&a),
+            ObjectiveSense::Maximize => a.total_cmp(&b),
+        }
+    }
+
+    pub fn is_better(&self, a: Fitness, b: Fitness) -> bool {
+        self.compare(a, b) == Ordering::Greater
+    }
+
+    // How much better b is than a, negative when it is worse
+    pub fn improvement(&self, a: Fitness, b: Fitness) -> Fitness {
+        match self {
+            ObjectiveSense::Minimize => a - b,
+            ObjectiveSense::Maximize => b - a,
+        }
+    }
+
+    // Value every real objective value is at least as good as
+    pub fn worst(&self) -> Fitness {
+        match self {
+            ObjectiveSense::Minimize => Fitness::INFINITY,
+            ObjectiveSense::Maximize => -Fitness::INFINITY,
+        }
+    }
+}
+
+// Problems are shared with the threads of a parallel evaluation
+pub trait Problem<I: Individual = VecIndividual>: Sync {
+    fn eval(&self, individual: &I) -> Result<Fitness>;
+    fn random_individual(&self, rng: &mut dyn RngCore) -> I;
+    fn serialize_indiviual(&self, individual: &I) -> String;
+
+    // Most problems minimize a cost, those maximizing the objective override it
+    fn sense(&self) -> ObjectiveSense {
+        ObjectiveSense::Minimize
+    }
+
+    // Objective value of the neighbor the move leads to, problems that can evaluate
+    // it without applying the move override it. Values rather than deltas are returned,
+    // so that moves between infeasible (infinite) and feasible individuals stay comparable.
+    fn eval_move(&self, individual: &I, step: &I::Move) -> Result<Fitness> {
+        let mut neighbor = individual.clone();
+        neighbor.apply_move(step);
+        self.eval(&neighbor)
+    }
 
-pub trait Problem {
-    fn eval(&self, individual: &VecIndividual) -> Result<Fitness>;
-    fn random_individual(&self) -> VecIndividual;
-    fn serialize_indiviual(&self, individual: &VecIndividual) -> String;
+    // Values of a whole neighborhood, lets problems share the work done on the individual
+    fn eval_moves(&self, individual: &I, steps: &[I::Move]) -> Result<Vec<Fitness>> {
+        steps
+            .iter()
+            .map(|step| self.eval_move(individual, step))
+            .collect()
+    }
 }
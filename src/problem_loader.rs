@@ -1,21 +1,34 @@
-use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::individual::{moves::Move, Fitness, Gene, Individual, VecIndividual};
 use crate::problem::Problem;
-use anyhow::{anyhow, Ok, Result};
-
+use anyhow::{anyhow, Context, Ok, Result};
+use best_known::BestKnownSolution;
+use decoder::{Decoder, DeliveryMode, DepotAssignment, Route};
+use distance::{distance_matrix, DistanceFunction, DistancePrecision};
+use fleet::{DepotFleet, FleetLimit};
+use parser::{ParseError, TsplibInstance};
+use rand::RngCore;
+use solution_file::SolutionFile;
+use std::fs::read_to_string;
+use std::path::Path;
+use vehicle::{VehicleAssignment, VehicleType};
+
+pub mod best_known;
+pub mod decoder;
+mod delta;
+pub mod distance;
+pub mod fleet;
+pub mod parser;
+pub mod solution_file;
 #[cfg(test)]
 mod tests;
+pub mod vehicle;
 
-#[derive(Debug)]
-pub struct Coordinates {
-    pub x: u16,
-    pub y: u16,
-}
+pub type Coordinate = f64;
 
-impl Coordinates {
-    pub fn distance(&self, other: &Coordinates) -> f32 {
-        let sum = (self.x as i16 - other.x as i16).pow(2) + (self.y as i16 - other.y as i16).pow(2);
-        f32::from(sum).sqrt()
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub x: Coordinate,
+    pub y: Coordinate,
 }
 
 pub type Demand = u16;
@@ -26,22 +39,40 @@ pub struct CVRProblem {
     comment: String,
     type_: String,
     edge_weight_type: String,
+    distance_function: DistanceFunction,
+    distance_precision: DistancePrecision,
     capacity: Demand,
     dimension: usize,
     all_nodes: Vec<Coordinates>,
     stops: Vec<Gene>,
     depots: Vec<Gene>,
     demands: Vec<Demand>,
+    // Empty when every node is served instantly
+    service_times: Vec<Fitness>,
+    // Limits of the vehicles at every depot, in the order of the depots
+    depot_fleets: Vec<DepotFleet>,
     distances: Option<Vec<Vec<Fitness>>>,
+    best_known: Option<BestKnownSolution>,
+    decoder: Decoder,
+    fleet_limit: FleetLimit,
+    depot_assignment: DepotAssignment,
+    // Empty for a homogeneous fleet of vehicles with the instance capacity
+    vehicle_types: Vec<VehicleType>,
+    vehicle_assignment: VehicleAssignment,
+    delivery_mode: DeliveryMode,
 }
 
 impl Problem for CVRProblem {
-    fn random_individual(&self) -> VecIndividual {
-        let mut left_nodes = VecIndividual::from(&self.stops);
+    fn random_individual(&self, rng: &mut dyn RngCore) -> VecIndividual {
+        let nodes = match self.depot_assignment {
+            DepotAssignment::Closest => self.stops.clone(),
+            DepotAssignment::Encoded => [self.stops.as_slice(), &self.depots].concat(),
+        };
+        let mut left_nodes = VecIndividual::from(&nodes);
         let mut selected_nodes: VecIndividual = VecIndividual::new();
 
-        for _ in &self.stops {
-            let selected_gene = left_nodes.random_gene();
+        for _ in &nodes {
+            let selected_gene = left_nodes.random_gene(rng);
             selected_nodes.add_gene(selected_gene);
             left_nodes.remove_gene(selected_gene)
         }
@@ -50,55 +81,38 @@ impl Problem for CVRProblem {
     }
 
     fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
-        struct CurrentState {
-            node: Gene,
-            distance: Fitness,
-            resources: u16,
-        }
+        self.total_cost(individual)
+    }
 
-        let initial_state = CurrentState {
-            node: self.closest_depot(),
-            distance: Fitness::default(),
-            resources: self.capacity,
-        };
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        self.solution_file(individual)
+            .map(|solution| solution.to_string())
+            .unwrap_or_else(|err| format!("Invalid individual: {}", err))
+    }
 
-        let evaluation =
-            individual
-                .genes()
-                .iter()
-                .try_fold(initial_state, |mut accum, &curr| {
-                    let next_demand = &self.demands(&curr)?;
-                    let distance = if next_demand > &accum.resources {
-                        let depot = &self.closest_depot();
-                        accum.resources = self.capacity - next_demand;
-                        self.distance(&accum.node, depot)? + self.distance(depot, &curr)?
-                    } else {
-                        accum.resources -= next_demand;
-                        self.distance(&accum.node, &curr)?
-                    };
-
-                    accum.distance += distance;
-                    accum.node = curr;
-                    Ok(accum)
-                })?;
-
-        let res = evaluation.distance + self.distance(&evaluation.node, &self.closest_depot())?;
-        Ok(self.fitness_from_distance(res))
+    fn eval_move(&self, individual: &VecIndividual, step: &Move) -> Result<Fitness> {
+        Ok(self.eval_moves(individual, std::slice::from_ref(step))?[0])
     }
 
-    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
-        format!(
-            "{:?}",
-            individual
-                .genes()
-                .iter()
-                .map(|gene| gene + 1)
-                .collect::<Vec<Gene>>(),
-        )
+    // The individual is decoded once for the whole neighborhood
+    fn eval_moves(&self, individual: &VecIndividual, steps: &[Move]) -> Result<Vec<Fitness>> {
+        if self.supports_move_delta() {
+            return self.move_values(individual, steps);
+        }
+
+        steps
+            .iter()
+            .map(|step| {
+                let mut neighbor = individual.clone();
+                neighbor.apply_move(step);
+                self.total_cost(&neighbor)
+            })
+            .collect()
     }
 }
 
 impl CVRProblem {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         comment: String,
@@ -116,6 +130,9 @@ impl CVRProblem {
             name,
             comment,
             type_,
+            distance_function: DistanceFunction::try_from(edge_weight_type.as_str())
+                .unwrap_or_default(),
+            distance_precision: DistancePrecision::default(),
             edge_weight_type,
             capacity,
             all_nodes,
@@ -123,23 +140,205 @@ impl CVRProblem {
             demands,
             dimension,
             depots,
+            service_times: Vec::new(),
+            depot_fleets: Vec::new(),
             distances,
+            best_known: None,
+            decoder: Decoder::default(),
+            fleet_limit: FleetLimit::default(),
+            depot_assignment: DepotAssignment::default(),
+            vehicle_types: Vec::new(),
+            vehicle_assignment: VehicleAssignment::default(),
+            delivery_mode: DeliveryMode::default(),
+        }
+    }
+
+    pub fn total_distance(&self, individual: &VecIndividual) -> Result<Fitness> {
+        Ok(self
+            .decode(individual)?
+            .iter()
+            .map(|route| route.length)
+            .sum())
+    }
+
+    // Distance, or the cost of the assigned vehicles for heterogeneous fleets,
+    // increased by the penalty for exceeding the fleet limit. Solutions breaking
+    // the limits of a depot fleet get the worst cost.
+    pub fn total_cost(&self, individual: &VecIndividual) -> Result<Fitness> {
+        let routes = self.decode(individual)?;
+        if !self.fits_depot_fleets(&routes) {
+            return Ok(Fitness::INFINITY);
         }
+
+        let cost: Fitness = match self.vehicle_types.is_empty() {
+            true => routes.iter().map(|route| route.length).sum(),
+            false => match self.assign_vehicles(&routes) {
+                Some(assignment) => routes
+                    .iter()
+                    .zip(assignment)
+                    .map(|(route, vehicle_type)| self.vehicle_types[vehicle_type].route_cost(route))
+                    .sum(),
+                None => Fitness::INFINITY,
+            },
+        };
+        Ok(cost + self.fleet_limit.penalty(routes.len()))
+    }
+
+    // Whether every depot has enough vehicles for its routes and each of them
+    // stays within the capacity and the duration limit of the depot
+    pub fn fits_depot_fleets(&self, routes: &[Route]) -> bool {
+        let mut used_vehicles = vec![0; self.depot_fleets.len()];
+        for route in routes {
+            let Some(index) = self.depots.iter().position(|depot| *depot == route.depot) else {
+                continue;
+            };
+            let Some(fleet) = self.depot_fleets.get(index) else {
+                continue;
+            };
+
+            used_vehicles[index] += 1;
+            let too_long = fleet
+                .max_duration
+                .is_some_and(|max_duration| self.route_duration(route) > max_duration);
+            if route.load > fleet.capacity || too_long {
+                return false;
+            }
+        }
+
+        self.depot_fleets
+            .iter()
+            .zip(used_vehicles)
+            .all(|(fleet, used)| fleet.vehicles.is_none_or(|vehicles| used <= vehicles))
+    }
+
+    // Travel time of the route, equal to its length, and the service times of its stops
+    pub fn route_duration(&self, route: &Route) -> Fitness {
+        route.length
+            + route
+                .stops
+                .iter()
+                .map(|stop| self.service_time(stop))
+                .sum::<Fitness>()
+    }
+
+    // Vehicle type serving every route, None when the fleet cannot serve all of them
+    pub fn assign_vehicles(&self, routes: &[Route]) -> Option<Vec<usize>> {
+        self.vehicle_assignment.assign(&self.vehicle_types, routes)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    pub fn problem_type(&self) -> &str {
+        &self.type_
+    }
+
+    pub fn edge_weight_type(&self) -> &str {
+        &self.edge_weight_type
+    }
+
+    pub fn decoder(&self) -> Decoder {
+        self.decoder
+    }
+
+    pub fn set_decoder(&mut self, decoder: Decoder) {
+        self.decoder = decoder;
+    }
+
+    pub fn fleet_limit(&self) -> FleetLimit {
+        self.fleet_limit
+    }
+
+    pub fn set_fleet_limit(&mut self, fleet_limit: FleetLimit) {
+        self.fleet_limit = fleet_limit;
+    }
+
+    pub fn depot_assignment(&self) -> DepotAssignment {
+        self.depot_assignment
+    }
+
+    pub fn set_depot_assignment(&mut self, depot_assignment: DepotAssignment) {
+        self.depot_assignment = depot_assignment;
+    }
+
+    pub fn vehicle_types(&self) -> &Vec<VehicleType> {
+        &self.vehicle_types
+    }
+
+    // Fails and keeps the previous fleet when a customer no longer fits into any vehicle
+    pub fn set_vehicle_types(&mut self, vehicle_types: Vec<VehicleType>) -> Result<()> {
+        let previous = std::mem::replace(&mut self.vehicle_types, vehicle_types);
+        self.validate_demands()
+            .inspect_err(|_| self.vehicle_types = previous)
+    }
+
+    pub fn vehicle_assignment(&self) -> VehicleAssignment {
+        self.vehicle_assignment
+    }
+
+    pub fn set_vehicle_assignment(&mut self, vehicle_assignment: VehicleAssignment) {
+        self.vehicle_assignment = vehicle_assignment;
+    }
+
+    pub fn delivery_mode(&self) -> DeliveryMode {
+        self.delivery_mode
+    }
+
+    // Fails and keeps the previous mode when a customer does not fit into a vehicle
+    pub fn set_delivery_mode(&mut self, delivery_mode: DeliveryMode) -> Result<()> {
+        let previous = std::mem::replace(&mut self.delivery_mode, delivery_mode);
+        self.validate_demands()
+            .inspect_err(|_| self.delivery_mode = previous)
+    }
+
+    pub fn best_known(&self) -> Option<&BestKnownSolution> {
+        self.best_known.as_ref()
+    }
+
+    pub fn is_explicit(&self) -> bool {
+        self.distance_function == DistanceFunction::Explicit
+    }
+
+    pub fn distance_function(&self) -> DistanceFunction {
+        self.distance_function
+    }
+
+    pub fn distance_precision(&self) -> DistancePrecision {
+        self.distance_precision
     }
 
-    // To make the fitness "the higher the better" the distance is inversed
-    pub fn fitness_from_distance(&self, distance: Fitness) -> Fitness {
-        1f32 / (1f32 + distance)
+    // Switches between exact distances and the TSPLIB rounded ones used by published optimal values
+    pub fn set_distance_precision(&mut self, precision: DistancePrecision) {
+        self.distance_precision = precision;
+        self.precalculate_distances();
     }
 
     pub fn stops(&self) -> &Vec<Gene> {
         &self.stops
     }
 
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
     pub fn capacity(&self) -> Demand {
         self.capacity
     }
 
+    // Largest load a single route may carry, the biggest vehicle for heterogeneous fleets
+    pub fn route_capacity(&self) -> Demand {
+        self.vehicle_types
+            .iter()
+            .map(|vehicle_type| vehicle_type.capacity)
+            .max()
+            .unwrap_or(self.capacity)
+    }
+
     pub fn distance(&self, node_a: &Gene, node_b: &Gene) -> Result<Fitness> {
         match &self.distances {
             None => Err(anyhow!(
@@ -156,7 +355,7 @@ impl CVRProblem {
     }
 
     fn separate_stops_and_depots(&mut self) {
-        self.stops = (0..self.all_nodes.len() as Gene)
+        self.stops = (0..self.dimension as Gene)
             .filter(|node| {
                 let node_gene = *node as Gene;
                 !self.depots.contains(&node_gene)
@@ -171,144 +370,119 @@ impl CVRProblem {
         }
     }
 
-    pub fn closest_depot(&self) -> Gene {
-        self.depots[0]
+    pub fn depots(&self) -> &Vec<Gene> {
+        &self.depots
     }
 
-    pub fn precalculate_distances(&mut self) {
-        self.distances = Some(vec![vec![0f32; self.dimension]; self.dimension]);
-
-        for i in 0usize..self.dimension {
-            for j in 0usize..self.dimension {
-                match &mut self.distances {
-                    None => {
-                        continue;
-                    }
-                    Some(distances) => {
-                        distances[i][j] = self.all_nodes[i].distance(&self.all_nodes[j]);
-                    }
-                }
-            }
-        }
+    pub fn depot_fleets(&self) -> &Vec<DepotFleet> {
+        &self.depot_fleets
     }
-}
-
-#[derive(Copy, Clone)]
-enum ProblemLoadingStage {
-    Beginning,
-    NodeCoordinates,
-    Demands,
-    Depot,
-    Finished,
-}
 
-impl From<String> for CVRProblem {
-    fn from(content: String) -> Self {
-        let current_stage = ProblemLoadingStage::Beginning;
-        let initial_problem = CVRProblem::default();
-
-        let mut problem = content
-            .lines()
-            .fold(
-                (initial_problem, current_stage),
-                |accum, line| match accum.1 {
-                    ProblemLoadingStage::Finished => accum,
-                    ProblemLoadingStage::Demands => handle_loading_problem_demands(accum, line),
-                    ProblemLoadingStage::NodeCoordinates => {
-                        handle_loading_problem_node_coordinates(accum, line)
-                    }
-                    ProblemLoadingStage::Depot => handle_loading_problem_depots(accum, line),
-                    _ => handle_loading_problem_param(accum, line),
-                },
-            )
-            .0;
+    pub fn set_depot_fleets(&mut self, depot_fleets: Vec<DepotFleet>) {
+        self.depot_fleets = depot_fleets;
+    }
 
-        problem.separate_stops_and_depots();
-        problem
+    pub fn service_time(&self, node: &Gene) -> Fitness {
+        self.service_times
+            .get(*node as usize)
+            .copied()
+            .unwrap_or_default()
     }
-}
 
-fn handle_loading_problem_demands(
-    mut problem: (CVRProblem, ProblemLoadingStage),
-    line: &str,
-) -> (CVRProblem, ProblemLoadingStage) {
-    let row = line.trim().split(" ").collect::<Vec<&str>>();
-    match row.len() {
-        1 => (problem.0, ProblemLoadingStage::Depot),
-        _ => {
-            let demand = row[1].parse::<u16>().unwrap();
-            problem.0.demands.push(demand);
-            problem
+    pub fn closest_depot(&self, node: &Gene) -> Result<Gene> {
+        let mut closest = (self.depots[0], Fitness::INFINITY);
+        for depot in &self.depots {
+            let distance = self.distance(node, depot)?;
+            if distance < closest.1 {
+                closest = (*depot, distance);
+            }
         }
+        Ok(closest.0)
     }
-}
 
-fn handle_loading_problem_node_coordinates(
-    mut problem: (CVRProblem, ProblemLoadingStage),
-    line: &str,
-) -> (CVRProblem, ProblemLoadingStage) {
-    let row = line.trim().split(" ").collect::<Vec<&str>>();
-    match row.len() {
-        1 => (problem.0, ProblemLoadingStage::Demands),
-        2 => (problem.0, ProblemLoadingStage::Demands),
-        _ => {
-            let x = row[1].parse::<u16>().unwrap();
-            let y = row[2].parse::<u16>().unwrap();
-            problem.0.all_nodes.push(Coordinates { x, y });
-            problem
+    // Distances of EXPLICIT instances are read straight from the file and never recalculated
+    pub fn precalculate_distances(&mut self) {
+        if self.is_explicit() {
+            return;
         }
+
+        self.distances = Some(distance_matrix(
+            self.distance_function,
+            &self.all_nodes,
+            self.distance_precision,
+        ));
     }
 }
 
-fn handle_loading_problem_depots(
-    mut problem: (CVRProblem, ProblemLoadingStage),
-    line: &str,
-) -> (CVRProblem, ProblemLoadingStage) {
-    let row = line.trim().parse::<i16>().unwrap();
-    match row {
-        -1 => (problem.0, ProblemLoadingStage::Finished),
-        val => {
-            problem.0.depots.push(val as u16 - 1);
+impl CVRProblem {
+    pub fn parse(content: &str) -> Result<CVRProblem, ParseError> {
+        TsplibInstance::parse(content).map(CVRProblem::from_instance)
+    }
+
+    pub fn parse_cordeau(content: &str) -> Result<CVRProblem, ParseError> {
+        TsplibInstance::parse_cordeau(content).map(CVRProblem::from_instance)
+    }
+
+    // Loads the instance together with an optional CVRPLIB .sol file lying next to it
+    pub fn from_file(path: &str) -> Result<CVRProblem> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let mut problem =
+            CVRProblem::parse(&content).with_context(|| format!("Failed to parse {}", path))?;
+
+        let solution_path = Path::new(path).with_extension("sol");
+        if solution_path.exists() {
+            let solution = read_to_string(&solution_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| SolutionFile::parse(&content))
+                .with_context(|| format!("Failed to load {}", solution_path.display()))?;
             problem
+                .best_known
+                .get_or_insert_with(BestKnownSolution::default)
+                .merge_solution_file(solution);
         }
+
+        Ok(problem)
+    }
+
+    fn from_instance(instance: TsplibInstance) -> CVRProblem {
+        let dimension = instance.dimension.unwrap_or_default();
+        let best_known = BestKnownSolution::from_instance(&instance.name, &instance.comment);
+
+        let mut problem = CVRProblem {
+            name: instance.name,
+            comment: instance.comment,
+            type_: instance.type_,
+            edge_weight_type: instance.edge_weight_type,
+            distance_function: instance.distance_function,
+            best_known,
+            capacity: instance.capacity.unwrap_or_default(),
+            dimension,
+            all_nodes: instance.node_coordinates.into_iter().flatten().collect(),
+            demands: instance.demands.into_iter().flatten().collect(),
+            service_times: instance.service_times,
+            depot_fleets: instance.depot_fleets,
+            fleet_limit: instance
+                .vehicles
+                .map_or(FleetLimit::Unlimited, FleetLimit::Hard),
+            distances: instance.edge_weights,
+            depots: instance
+                .depots
+                .into_iter()
+                .map(|depot| depot as Gene)
+                .collect(),
+            ..CVRProblem::default()
+        };
+
+        problem.separate_stops_and_depots();
+        problem.precalculate_distances();
+        problem
     }
 }
 
-fn handle_loading_problem_param(
-    mut problem: (CVRProblem, ProblemLoadingStage),
-    line: &str,
-) -> (CVRProblem, ProblemLoadingStage) {
-    let row = line.trim().split(" : ").collect::<Vec<&str>>();
-    match row[0] {
-        "NAME" => {
-            problem.0.name = row[1].to_string();
-        }
-        "COMMENT" => {
-            problem.0.comment = row[1].to_string();
-        }
-        "TYPE" => {
-            problem.0.type_ = row[1].to_string();
-        }
-        "DIMENSION" => {
-            problem.0.dimension = row[1].to_string().parse::<usize>().unwrap();
-        }
-        "EDGE_WEIGHT_TYPE" => {
-            problem.0.edge_weight_type = row[1].to_string();
-        }
-        "CAPACITY" => {
-            problem.0.capacity = row[1].to_string().parse::<u16>().unwrap();
-        }
-        "NODE_COORD_SECTION" => {
-            problem.1 = ProblemLoadingStage::NodeCoordinates;
-        }
-        "DEMAND_SECTION" => {
-            problem.1 = ProblemLoadingStage::Demands;
-        }
-        "DEPOT_SECTION" => {
-            problem.1 = ProblemLoadingStage::Depot;
-        }
-        _ => {}
-    };
+impl TryFrom<&str> for CVRProblem {
+    type Error = ParseError;
 
-    problem
+    fn try_from(content: &str) -> Result<Self, Self::Error> {
+        CVRProblem::parse(content)
+    }
 }
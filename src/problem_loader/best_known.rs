@@ -0,0 +1,65 @@
+use crate::individual::Fitness;
+
+use super::solution_file::SolutionFile;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BestKnownSolution {
+    pub vehicles: Option<usize>,
+    pub cost: Option<Fitness>,
+    pub routes: Vec<Vec<usize>>,
+}
+
+impl BestKnownSolution {
+    // Reads values like "No of trucks: 5, Optimal value: 661" from the instance COMMENT,
+    // falling back to the "-k5" suffix of the instance name for the number of vehicles
+    pub fn from_instance(name: &str, comment: &str) -> Option<BestKnownSolution> {
+        let comment = comment.to_lowercase();
+        let vehicles = number_after(&comment, "no of trucks")
+            .and_then(|vehicles| vehicles.parse::<usize>().ok())
+            .or_else(|| vehicles_from_name(name));
+        let cost = number_after(&comment, "optimal value")
+            .or_else(|| number_after(&comment, "best value"))
+            .and_then(|cost| cost.parse::<Fitness>().ok());
+
+        match (vehicles, cost) {
+            (None, None) => None,
+            (vehicles, cost) => Some(BestKnownSolution {
+                vehicles,
+                cost,
+                routes: Vec::new(),
+            }),
+        }
+    }
+
+    // A solution file is more recent than the instance COMMENT, so its values take precedence
+    pub fn merge_solution_file(&mut self, solution: SolutionFile) {
+        self.vehicles = Some(solution.routes.len());
+        self.cost = solution.cost.or(self.cost);
+        self.routes = solution.routes;
+    }
+
+    // Percentage gap between the given cost and the best known one
+    pub fn gap(&self, cost: Fitness) -> Option<Fitness> {
+        self.cost
+            .filter(|best| *best > 0.0)
+            .map(|best| 100.0 * (cost - best) / best)
+    }
+}
+
+fn number_after<'a>(text: &'a str, label: &str) -> Option<&'a str> {
+    let start = text.find(label)? + label.len();
+    let rest = text[start..].trim_start_matches([':', ' ', '\t']);
+    let end = rest
+        .find(|character: char| !(character.is_ascii_digit() || character == '.'))
+        .unwrap_or(rest.len());
+
+    match &rest[..end] {
+        "" => None,
+        number => Some(number),
+    }
+}
+
+fn vehicles_from_name(name: &str) -> Option<usize> {
+    name.rsplit_once("-k")
+        .and_then(|(_, vehicles)| vehicles.parse::<usize>().ok())
+}
@@ -0,0 +1,353 @@
+use anyhow::{anyhow, Result};
+
+use crate::individual::{Fitness, Gene, VecIndividual};
+
+use super::{fleet::FleetLimit, CVRProblem, Demand};
+
+// Single vehicle trip, starting and ending at the depot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub depot: Gene,
+    pub stops: Vec<Gene>,
+    // Amount delivered at every stop, less than the demand only for split deliveries
+    pub deliveries: Vec<Demand>,
+    pub load: Demand,
+    pub length: Fitness,
+}
+
+// Strategy used to split the giant tour encoded by the individual into routes
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Decoder {
+    // Starts a new route whenever the next customer no longer fits into the vehicle
+    #[default]
+    Greedy,
+    // Prins' Split, finds the cheapest partition of the giant tour preserving its order
+    OptimalSplit,
+}
+
+// How the depot of every route is chosen in multi-depot problems
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DepotAssignment {
+    // Every route starts and ends at the depot closing it with the shortest detour
+    #[default]
+    Closest,
+    // Depots are genes of the individual, customers are served from the depot
+    // preceding them in the (cyclic) permutation
+    Encoded,
+}
+
+// Whether a customer may be served by more than one route
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DeliveryMode {
+    // Every customer gets its whole demand from a single vehicle
+    #[default]
+    Single,
+    // Vehicles are filled up to the capacity, customers that do not fit get the rest of
+    // their demand from the next route. The chosen decoder is not used in this mode.
+    Split,
+}
+
+impl CVRProblem {
+    pub fn decode(&self, individual: &VecIndividual) -> Result<Vec<Route>> {
+        let mut routes = Vec::new();
+        for (depot, tour) in self.depot_segments(individual.genes()) {
+            routes.extend(self.decode_tour(&tour, depot)?);
+        }
+        Ok(routes)
+    }
+
+    // Splits an encoded individual into the customers served by every depot,
+    // individuals without depot genes form a single segment with a free depot choice
+    fn depot_segments(&self, genes: &[Gene]) -> Vec<(Option<Gene>, Vec<Gene>)> {
+        let first_depot = match self.depot_assignment {
+            DepotAssignment::Closest => None,
+            DepotAssignment::Encoded => genes.iter().position(|gene| self.depots.contains(gene)),
+        };
+
+        let Some(first_depot) = first_depot else {
+            return vec![(None, genes.to_vec())];
+        };
+
+        let mut segments: Vec<(Option<Gene>, Vec<Gene>)> = Vec::new();
+        for gene in genes[first_depot..].iter().chain(&genes[..first_depot]) {
+            match segments.last_mut() {
+                Some((_, tour)) if !self.depots.contains(gene) => tour.push(*gene),
+                _ => segments.push((Some(*gene), Vec::new())),
+            }
+        }
+        segments
+    }
+
+    fn decode_tour(&self, tour: &[Gene], depot: Option<Gene>) -> Result<Vec<Route>> {
+        if self.delivery_mode == DeliveryMode::Split {
+            return self
+                .split_deliveries(tour)?
+                .into_iter()
+                .map(|stops| {
+                    let (stops, deliveries) = stops.into_iter().unzip();
+                    self.route(stops, deliveries, depot)
+                })
+                .collect();
+        }
+
+        let boundaries = match (self.decoder, self.fleet_limit, depot) {
+            (Decoder::Greedy, _, _) => self.greedy_split(tour)?,
+            // No partition fits into the fleet, the evaluation will mark it as infeasible
+            (Decoder::OptimalSplit, FleetLimit::Hard(vehicles), None) => {
+                match self.limited_split(tour, vehicles)? {
+                    Some(boundaries) => boundaries,
+                    None => self.greedy_split(tour)?,
+                }
+            }
+            (Decoder::OptimalSplit, _, _) => self.unlimited_split(tour, depot)?,
+        };
+
+        boundaries
+            .into_iter()
+            .map(|(start, end)| {
+                let stops = tour[start..end].to_vec();
+                let deliveries = stops
+                    .iter()
+                    .map(|stop| self.demands(stop))
+                    .collect::<Result<Vec<Demand>>>()?;
+                self.route(stops, deliveries, depot)
+            })
+            .collect()
+    }
+
+    fn route(
+        &self,
+        stops: Vec<Gene>,
+        deliveries: Vec<Demand>,
+        depot: Option<Gene>,
+    ) -> Result<Route> {
+        let depot = match depot {
+            Some(depot) => depot,
+            None => self.route_depot(&stops)?,
+        };
+
+        Ok(Route {
+            depot,
+            load: deliveries.iter().sum(),
+            length: self.route_length(depot, &stops)?,
+            stops,
+            deliveries,
+        })
+    }
+
+    // Without split deliveries a customer demanding more than the biggest vehicle can not be served.
+    // Checked whenever the capacity or the delivery mode changes, so decoding can rely on it.
+    pub fn validate_demands(&self) -> Result<()> {
+        if self.delivery_mode == DeliveryMode::Split {
+            return Ok(());
+        }
+
+        let capacity = self.route_capacity();
+        for gene in &self.stops {
+            let demand = self.demands(gene)?;
+            if demand > capacity {
+                return Err(anyhow!(
+                    "Customer {} demands {}, which exceeds the vehicle capacity {}, use split deliveries to serve it",
+                    gene,
+                    demand,
+                    capacity
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Fills every vehicle up to the capacity, splitting the demand of the customer that does not fit
+    fn split_deliveries(&self, tour: &[Gene]) -> Result<Vec<Vec<(Gene, Demand)>>> {
+        let capacity = self.route_capacity();
+        if capacity == 0 {
+            return Err(anyhow!("Split deliveries need a positive vehicle capacity"));
+        }
+
+        let mut routes: Vec<Vec<(Gene, Demand)>> = Vec::new();
+        let mut route = Vec::new();
+        let mut load: Demand = 0;
+
+        for gene in tour {
+            let mut remaining = self.demands(gene)?;
+            loop {
+                if load == capacity && !route.is_empty() {
+                    routes.push(std::mem::take(&mut route));
+                    load = 0;
+                }
+
+                let delivered = remaining.min(capacity - load);
+                route.push((*gene, delivered));
+                load += delivered;
+                remaining -= delivered;
+                if remaining == 0 {
+                    break;
+                }
+            }
+        }
+
+        if !route.is_empty() {
+            routes.push(route);
+        }
+        Ok(routes)
+    }
+
+    // Depot closing the route with the shortest detour
+    pub fn route_depot(&self, stops: &[Gene]) -> Result<Gene> {
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return Ok(self.depots[0]);
+        };
+
+        let mut best = (self.depots[0], Fitness::INFINITY);
+        for depot in &self.depots {
+            let detour = self.distance(depot, first)? + self.distance(last, depot)?;
+            if detour < best.1 {
+                best = (*depot, detour);
+            }
+        }
+        Ok(best.0)
+    }
+
+    // Starts a new route whenever the next customer no longer fits into the vehicle
+    pub(super) fn greedy_split(&self, tour: &[Gene]) -> Result<Vec<(usize, usize)>> {
+        let mut boundaries: Vec<(usize, usize)> = Vec::new();
+        let mut load: Demand = 0;
+
+        for (index, gene) in tour.iter().enumerate() {
+            let demand = self.demands(gene)?;
+            match boundaries.last_mut() {
+                Some((_, end))
+                    if load
+                        .checked_add(demand)
+                        .is_some_and(|load| load <= self.route_capacity()) =>
+                {
+                    load += demand;
+                    *end = index + 1;
+                }
+                _ => {
+                    load = demand;
+                    boundaries.push((index, index + 1));
+                }
+            }
+        }
+
+        Ok(boundaries)
+    }
+
+    // Shortest path over an auxiliary DAG, where an arc (i, j) is the route serving
+    // customers i+1..=j of the tour
+    fn unlimited_split(&self, tour: &[Gene], depot: Option<Gene>) -> Result<Vec<(usize, usize)>> {
+        let mut costs = vec![Fitness::INFINITY; tour.len() + 1];
+        let mut predecessors = vec![0usize; tour.len() + 1];
+        costs[0] = Fitness::default();
+
+        for start in 0..tour.len() {
+            for (end, length) in self.split_arcs(tour, start, depot)? {
+                if costs[start] + length < costs[end] {
+                    costs[end] = costs[start] + length;
+                    predecessors[end] = start;
+                }
+            }
+        }
+
+        let mut boundaries = Vec::new();
+        let mut end = tour.len();
+        while end > 0 {
+            boundaries.push((predecessors[end], end));
+            end = predecessors[end];
+        }
+
+        boundaries.reverse();
+        Ok(boundaries)
+    }
+
+    // Same shortest path, layered by the number of routes used so far
+    fn limited_split(&self, tour: &[Gene], vehicles: usize) -> Result<Option<Vec<(usize, usize)>>> {
+        let layers = vehicles.min(tour.len());
+        let mut costs = vec![vec![Fitness::INFINITY; tour.len() + 1]; layers + 1];
+        let mut predecessors = vec![vec![0usize; tour.len() + 1]; layers + 1];
+        costs[0][0] = Fitness::default();
+
+        for layer in 1..=layers {
+            for start in 0..tour.len() {
+                if costs[layer - 1][start] == Fitness::INFINITY {
+                    continue;
+                }
+
+                for (end, length) in self.split_arcs(tour, start, None)? {
+                    if costs[layer - 1][start] + length < costs[layer][end] {
+                        costs[layer][end] = costs[layer - 1][start] + length;
+                        predecessors[layer][end] = start;
+                    }
+                }
+            }
+        }
+
+        let best_layer = (0..=layers)
+            .filter(|layer| costs[*layer][tour.len()] < Fitness::INFINITY)
+            .min_by(|a, b| costs[*a][tour.len()].total_cmp(&costs[*b][tour.len()]));
+        let Some(mut layer) = best_layer else {
+            return Ok(None);
+        };
+
+        let mut boundaries = Vec::new();
+        let mut end = tour.len();
+        while end > 0 {
+            boundaries.push((predecessors[layer][end], end));
+            end = predecessors[layer][end];
+            layer -= 1;
+        }
+
+        boundaries.reverse();
+        Ok(Some(boundaries))
+    }
+
+    // Routes starting at the given position of the tour, as (end, length) pairs
+    fn split_arcs(
+        &self,
+        tour: &[Gene],
+        start: usize,
+        depot: Option<Gene>,
+    ) -> Result<Vec<(usize, Fitness)>> {
+        let capacity = self.route_capacity();
+        let depots = match depot {
+            Some(depot) => vec![depot],
+            None => self.depots.clone(),
+        };
+        let mut arcs = Vec::new();
+        let mut load: Demand = 0;
+        let mut path_length = Fitness::default();
+
+        for end in start..tour.len() {
+            // A load overflowing the demand type exceeds the capacity as well
+            load = match load.checked_add(self.demands(&tour[end])?) {
+                Some(load) if end == start || load <= capacity => load,
+                _ => break,
+            };
+            if end > start {
+                path_length += self.distance(&tour[end - 1], &tour[end])?;
+            }
+
+            let mut closing = Fitness::INFINITY;
+            for depot in &depots {
+                closing = closing
+                    .min(self.distance(depot, &tour[start])? + self.distance(&tour[end], depot)?);
+            }
+            arcs.push((end + 1, path_length + closing));
+        }
+
+        Ok(arcs)
+    }
+
+    pub fn route_length(&self, depot: Gene, stops: &[Gene]) -> Result<Fitness> {
+        let mut length = Fitness::default();
+        let mut previous = depot;
+
+        for stop in stops {
+            length += self.distance(&previous, stop)?;
+            previous = *stop;
+        }
+
+        Ok(length + self.distance(&previous, &depot)?)
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+
+use crate::individual::{moves::Move, Fitness, Gene, VecIndividual};
+
+use super::{
+    decoder::{Decoder, DeliveryMode, DepotAssignment},
+    CVRProblem, Demand,
+};
+
+// Greedy decoding of the individual the moves are applied to
+struct GreedyTour {
+    // (start, end) positions of every route in the giant tour
+    boundaries: Vec<(usize, usize)>,
+    lengths: Vec<Fitness>,
+    distance: Fitness,
+}
+
+impl CVRProblem {
+    // Moves can be scored by re-splitting only the routes they touch when the greedy
+    // decoder is used and the cost is the travelled distance
+    pub fn supports_move_delta(&self) -> bool {
+        self.decoder == Decoder::Greedy
+            && self.delivery_mode == DeliveryMode::Single
+            && self.depot_assignment == DepotAssignment::Closest
+            && self.vehicle_types.is_empty()
+            && self.depot_fleets.is_empty()
+    }
+
+    pub(super) fn move_values(
+        &self,
+        individual: &VecIndividual,
+        steps: &[Move],
+    ) -> Result<Vec<Fitness>> {
+        let genes = individual.genes();
+
+        let boundaries = self.greedy_split(genes)?;
+        let lengths = boundaries
+            .iter()
+            .map(|(start, end)| self.closest_depot_route_length(&genes[*start..*end]))
+            .collect::<Result<Vec<Fitness>>>()?;
+        let tour = GreedyTour {
+            distance: lengths.iter().sum(),
+            boundaries,
+            lengths,
+        };
+
+        steps
+            .iter()
+            .map(|step| self.move_value(genes, &tour, step))
+            .collect()
+    }
+
+    // Greedy split is repeated from the first route touched by the move, until a new route
+    // starts at the same position as in the original tour after the last changed gene.
+    // From there on both tours are split identically. The route ending right before the first
+    // changed gene is touched as well, as the new gene may fit into it.
+    fn move_value(&self, genes: &[Gene], tour: &GreedyTour, step: &Move) -> Result<Fitness> {
+        let cost = tour.distance + self.fleet_limit.penalty(tour.boundaries.len());
+        let Some((first_changed, last_changed)) = step.affected_range() else {
+            return Ok(cost);
+        };
+        let first_route = tour
+            .boundaries
+            .partition_point(|(_, end)| *end < first_changed);
+        let Some((start, _)) = tour.boundaries.get(first_route) else {
+            return Ok(cost);
+        };
+
+        let capacity = self.route_capacity();
+        let mut stops: Vec<Gene> = Vec::new();
+        let mut load: Demand = 0;
+        let mut new_distance = Fitness::default();
+        let mut new_routes = 0;
+        let mut synchronized_route = tour.boundaries.len();
+
+        for index in *start..genes.len() {
+            let gene = step.gene_at(genes, index);
+            let demand = self.demands(&gene)?;
+
+            let fits = load
+                .checked_add(demand)
+                .is_some_and(|load| load <= capacity);
+            if !stops.is_empty() && !fits {
+                new_distance += self.closest_depot_route_length(&stops)?;
+                new_routes += 1;
+                stops.clear();
+                load = 0;
+
+                if index > last_changed {
+                    if let Ok(route) = tour
+                        .boundaries
+                        .binary_search_by_key(&index, |(start, _)| *start)
+                    {
+                        synchronized_route = route;
+                        break;
+                    }
+                }
+            }
+
+            stops.push(gene);
+            load += demand;
+        }
+
+        if !stops.is_empty() {
+            new_distance += self.closest_depot_route_length(&stops)?;
+            new_routes += 1;
+        }
+
+        let old_distance: Fitness = tour.lengths[first_route..synchronized_route].iter().sum();
+        let routes = tour.boundaries.len() - (synchronized_route - first_route) + new_routes;
+
+        Ok(tour.distance - old_distance + new_distance + self.fleet_limit.penalty(routes))
+    }
+
+    fn closest_depot_route_length(&self, stops: &[Gene]) -> Result<Fitness> {
+        self.route_length(self.route_depot(stops)?, stops)
+    }
+}
@@ -0,0 +1,146 @@
+use crate::individual::Fitness;
+
+use super::Coordinates;
+
+// TSPLIB defines GEO distances with this truncated value of pi
+#[allow(clippy::approx_constant)]
+const GEO_PI: f64 = 3.141592;
+const EARTH_RADIUS: f64 = 6378.388;
+
+// Distance functions selected by EDGE_WEIGHT_TYPE, as defined by TSPLIB
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DistanceFunction {
+    #[default]
+    Euclidean2D,
+    Ceil2D,
+    Manhattan2D,
+    Maximum2D,
+    Geographical,
+    PseudoEuclidean,
+    Explicit,
+}
+
+// Rounded distances follow the TSPLIB integer conventions, which published optimal values use
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DistancePrecision {
+    #[default]
+    Exact,
+    Rounded,
+}
+
+impl TryFrom<&str> for DistanceFunction {
+    type Error = String;
+
+    fn try_from(edge_weight_type: &str) -> Result<Self, Self::Error> {
+        match edge_weight_type {
+            "EUC_2D" => Ok(Self::Euclidean2D),
+            "CEIL_2D" => Ok(Self::Ceil2D),
+            "MAN_2D" => Ok(Self::Manhattan2D),
+            "MAX_2D" => Ok(Self::Maximum2D),
+            "GEO" => Ok(Self::Geographical),
+            "ATT" => Ok(Self::PseudoEuclidean),
+            "EXPLICIT" => Ok(Self::Explicit),
+            other => Err(other.to_string()),
+        }
+    }
+}
+
+impl DistanceFunction {
+    pub fn distance(
+        &self,
+        node_a: &Coordinates,
+        node_b: &Coordinates,
+        precision: DistancePrecision,
+    ) -> f64 {
+        let dx = node_a.x - node_b.x;
+        let dy = node_a.y - node_b.y;
+        let rounded = precision == DistancePrecision::Rounded;
+
+        match self {
+            Self::Euclidean2D | Self::Explicit => {
+                let distance = (dx * dx + dy * dy).sqrt();
+                if rounded {
+                    nint(distance)
+                } else {
+                    distance
+                }
+            }
+            Self::Ceil2D => {
+                let distance = (dx * dx + dy * dy).sqrt();
+                if rounded {
+                    distance.ceil()
+                } else {
+                    distance
+                }
+            }
+            Self::Manhattan2D => {
+                let distance = dx.abs() + dy.abs();
+                if rounded {
+                    nint(distance)
+                } else {
+                    distance
+                }
+            }
+            Self::Maximum2D => {
+                if rounded {
+                    nint(dx.abs()).max(nint(dy.abs()))
+                } else {
+                    dx.abs().max(dy.abs())
+                }
+            }
+            Self::PseudoEuclidean => {
+                let distance = ((dx * dx + dy * dy) / 10.0).sqrt();
+                if !rounded {
+                    return distance;
+                }
+                let truncated = nint(distance);
+                if truncated < distance {
+                    truncated + 1.0
+                } else {
+                    truncated
+                }
+            }
+            Self::Geographical => {
+                let (latitude_a, longitude_a) = (to_radians(node_a.x), to_radians(node_a.y));
+                let (latitude_b, longitude_b) = (to_radians(node_b.x), to_radians(node_b.y));
+                let q1 = (longitude_a - longitude_b).cos();
+                let q2 = (latitude_a - latitude_b).cos();
+                let q3 = (latitude_a + latitude_b).cos();
+                let distance = EARTH_RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos();
+                if rounded {
+                    (distance + 1.0).trunc()
+                } else {
+                    distance
+                }
+            }
+        }
+    }
+}
+
+fn nint(value: f64) -> f64 {
+    (value + 0.5).trunc()
+}
+
+// GEO coordinates are given as DDD.MM (degrees and minutes)
+fn to_radians(coordinate: f64) -> f64 {
+    let degrees = coordinate.trunc();
+    let minutes = coordinate - degrees;
+    GEO_PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+// Distances between every pair of nodes
+pub fn distance_matrix(
+    function: DistanceFunction,
+    nodes: &[Coordinates],
+    precision: DistancePrecision,
+) -> Vec<Vec<Fitness>> {
+    nodes
+        .iter()
+        .map(|from| {
+            nodes
+                .iter()
+                .map(|to| function.distance(from, to, precision) as Fitness)
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,47 @@
+use crate::individual::Fitness;
+
+use super::Demand;
+
+// Maximum number of vehicles a solution may use
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FleetLimit {
+    #[default]
+    Unlimited,
+    // Solutions using more vehicles are infeasible and get the worst possible cost
+    Hard(usize),
+    // Every vehicle above the limit adds a penalty to the cost
+    Penalized {
+        vehicles: usize,
+        penalty: Fitness,
+    },
+}
+
+impl FleetLimit {
+    pub fn max_vehicles(&self) -> Option<usize> {
+        match self {
+            Self::Unlimited => None,
+            Self::Hard(vehicles) => Some(*vehicles),
+            Self::Penalized { vehicles, .. } => Some(*vehicles),
+        }
+    }
+
+    pub fn penalty(&self, used_vehicles: usize) -> Fitness {
+        let excess = used_vehicles.saturating_sub(self.max_vehicles().unwrap_or(usize::MAX));
+        match self {
+            _ if excess == 0 => Fitness::default(),
+            Self::Penalized { penalty, .. } => excess as Fitness * penalty,
+            _ => Fitness::INFINITY,
+        }
+    }
+}
+
+// Vehicles based at one depot of a multi-depot instance, routes breaking any of
+// the limits are infeasible
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepotFleet {
+    pub capacity: Demand,
+    // Travel and service times of a single route, None when routes are not limited
+    pub max_duration: Option<Fitness>,
+    // None for an unlimited number of vehicles
+    pub vehicles: Option<usize>,
+}
@@ -0,0 +1,622 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::individual::Fitness;
+
+use super::{distance::DistanceFunction, fleet::DepotFleet, Coordinates, Demand};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    UnknownKeyword(String),
+    MissingValue(String),
+    InvalidNumber(String),
+    MalformedRow(String),
+    MissingDimension,
+    NodeOutOfRange(usize),
+    DuplicateNode(usize),
+    DimensionMismatch {
+        section: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    MissingSection(&'static str),
+    MissingCapacity,
+    UnexpectedEnd(&'static str),
+    UnsupportedEdgeWeightType(String),
+    UnsupportedEdgeWeightFormat(String),
+    EdgeWeightCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKeyword(keyword) => write!(f, "unknown keyword '{}'", keyword),
+            Self::MissingValue(keyword) => write!(f, "missing value for '{}'", keyword),
+            Self::InvalidNumber(value) => write!(f, "invalid number '{}'", value),
+            Self::MalformedRow(row) => write!(f, "malformed row '{}'", row),
+            Self::MissingDimension => {
+                write!(f, "DIMENSION has to be declared before data sections")
+            }
+            Self::NodeOutOfRange(node) => write!(f, "node {} is outside of DIMENSION", node),
+            Self::DuplicateNode(node) => write!(f, "node {} is defined more than once", node),
+            Self::DimensionMismatch {
+                section,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} defines {} nodes, but DIMENSION is {}",
+                section, found, expected
+            ),
+            Self::MissingSection(section) => write!(f, "missing {}", section),
+            Self::MissingCapacity => write!(f, "missing CAPACITY"),
+            Self::UnexpectedEnd(expected) => write!(f, "file ends before {}", expected),
+            Self::UnsupportedEdgeWeightType(type_) => {
+                write!(f, "unsupported EDGE_WEIGHT_TYPE '{}'", type_)
+            }
+            Self::UnsupportedEdgeWeightFormat(format) => {
+                write!(f, "unsupported EDGE_WEIGHT_FORMAT '{}'", format)
+            }
+            Self::EdgeWeightCount { expected, found } => write!(
+                f,
+                "EDGE_WEIGHT_SECTION has {} weights, but {} were expected",
+                found, expected
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    // 1-based line number, None for errors found after the whole file was read
+    pub line: Option<usize>,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn at(line: usize, kind: ParseErrorKind) -> Self {
+        ParseError {
+            line: Some(line),
+            kind,
+        }
+    }
+
+    fn global(kind: ParseErrorKind) -> Self {
+        ParseError { line: None, kind }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Raw content of a TSPLIB/CVRPLIB file, before it is turned into a concrete problem
+#[derive(Debug, Default)]
+pub struct TsplibInstance {
+    pub name: String,
+    pub comment: String,
+    pub type_: String,
+    pub dimension: Option<usize>,
+    pub capacity: Option<Demand>,
+    // Size of the whole fleet, VEHICLES of CVRPLIB files
+    pub vehicles: Option<usize>,
+    pub edge_weight_type: String,
+    pub distance_function: DistanceFunction,
+    pub edge_weight_format: String,
+    pub node_coord_type: String,
+    pub display_data_type: String,
+    pub node_coordinates: Vec<Option<Coordinates>>,
+    pub demands: Vec<Option<Demand>>,
+    pub depots: Vec<usize>,
+    // Limits of the vehicles based at every depot, empty when the instance has none
+    pub depot_fleets: Vec<DepotFleet>,
+    // Service time of every node, empty when the instance has none
+    pub service_times: Vec<Fitness>,
+    // Full distance matrix built from EDGE_WEIGHT_SECTION for EXPLICIT instances
+    pub edge_weights: Option<Vec<Vec<Fitness>>>,
+    raw_edge_weights: Vec<Fitness>,
+    // DISTANCE and SERVICE_TIME, turned into depot fleets and service times once the depots are known
+    max_distance: Option<Fitness>,
+    service_time: Option<Fitness>,
+    has_node_coord_section: bool,
+    has_demand_section: bool,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Section {
+    Specification,
+    NodeCoordinates,
+    Demands,
+    Depots,
+    EdgeWeights,
+    // Sections that do not affect the problem (display data, fixed edges, tours)
+    Skipped,
+    Finished,
+}
+
+impl TsplibInstance {
+    // Reads a CVRP instance, which besides the graph needs demands, a depot and the capacity
+    pub fn parse(content: &str) -> Result<TsplibInstance, ParseError> {
+        let mut instance = TsplibInstance::parse_graph(content)?;
+        instance.validate_vehicle_routing()?;
+        instance.apply_route_limits();
+        Ok(instance)
+    }
+
+    // Reads any TSPLIB instance defining a graph by coordinates or explicit edge weights
+    pub fn parse_graph(content: &str) -> Result<TsplibInstance, ParseError> {
+        let mut instance = TsplibInstance::default();
+        let mut section = Section::Specification;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let trimmed = line.trim();
+
+            if section == Section::Finished {
+                break;
+            }
+
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if section != Section::Specification && !starts_with_number(trimmed) {
+                section = Section::Specification;
+            }
+
+            section = match section {
+                Section::Finished => break,
+                Section::Specification => instance.parse_keyword(trimmed, line_number)?,
+                Section::NodeCoordinates => {
+                    instance.parse_node_coordinates(trimmed, line_number)?;
+                    section
+                }
+                Section::Demands => {
+                    instance.parse_demand(trimmed, line_number)?;
+                    section
+                }
+                Section::Depots => instance.parse_depot(trimmed, line_number)?,
+                Section::EdgeWeights => {
+                    instance.parse_edge_weights(trimmed, line_number)?;
+                    section
+                }
+                Section::Skipped => match trimmed {
+                    "-1" => Section::Specification,
+                    _ => section,
+                },
+            };
+        }
+
+        instance.validate_graph()?;
+        if instance.is_explicit() {
+            instance.edge_weights = Some(instance.build_edge_weight_matrix()?);
+        }
+        Ok(instance)
+    }
+
+    // Cordeau's MDVRP format: a "type m n t" header, t "duration capacity" lines,
+    // then n customer rows "i x y duration demand ..." followed by t depot rows.
+    // Customers become nodes 0..n and depots nodes n..n+t, each depot has m vehicles.
+    // A zero duration does not limit the routes.
+    pub fn parse_cordeau(content: &str) -> Result<TsplibInstance, ParseError> {
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (header_line, header) =
+            lines
+                .next()
+                .ok_or(ParseError::global(ParseErrorKind::UnexpectedEnd(
+                    "the header",
+                )))?;
+        let header = header
+            .split_whitespace()
+            .map(|value| parse_number::<usize>(value, header_line))
+            .collect::<Result<Vec<usize>, ParseError>>()?;
+        let [_, vehicles, customers, depots] = header[..] else {
+            return Err(ParseError::at(
+                header_line,
+                ParseErrorKind::MalformedRow(format!("{:?}", header)),
+            ));
+        };
+
+        let dimension = customers + depots;
+        let mut instance = TsplibInstance {
+            type_: "MDVRP".to_string(),
+            dimension: Some(dimension),
+            edge_weight_type: "EUC_2D".to_string(),
+            node_coordinates: vec![None; dimension],
+            demands: vec![None; dimension],
+            service_times: vec![Fitness::default(); dimension],
+            depots: (customers..dimension).collect(),
+            has_node_coord_section: true,
+            has_demand_section: true,
+            ..TsplibInstance::default()
+        };
+
+        for _ in 0..depots {
+            let (line_number, line) =
+                lines
+                    .next()
+                    .ok_or(ParseError::global(ParseErrorKind::UnexpectedEnd(
+                        "the depot limits",
+                    )))?;
+            let row = line.split_whitespace().collect::<Vec<&str>>();
+            if row.len() != 2 {
+                return Err(ParseError::at(
+                    line_number,
+                    ParseErrorKind::MalformedRow(line.to_string()),
+                ));
+            }
+            let max_duration = parse_number::<Fitness>(row[0], line_number)?;
+            let capacity = parse_number::<Demand>(row[1], line_number)?;
+            // Routes are split by the biggest vehicle, the depot fleets are checked afterwards
+            instance.capacity = Some(instance.capacity.unwrap_or_default().max(capacity));
+            instance.depot_fleets.push(DepotFleet {
+                capacity,
+                max_duration: (max_duration > 0.0).then_some(max_duration),
+                vehicles: Some(vehicles),
+            });
+        }
+
+        for index in 0..dimension {
+            let Some((line_number, line)) = lines.next() else {
+                return Err(ParseError::global(ParseErrorKind::DimensionMismatch {
+                    section: "customer and depot rows",
+                    expected: dimension,
+                    found: index,
+                }));
+            };
+            let row = line.split_whitespace().collect::<Vec<&str>>();
+            if row.len() < 5 {
+                return Err(ParseError::at(
+                    line_number,
+                    ParseErrorKind::MalformedRow(line.to_string()),
+                ));
+            }
+
+            instance.node_coordinates[index] = Some(Coordinates {
+                x: parse_number(row[1], line_number)?,
+                y: parse_number(row[2], line_number)?,
+            });
+            instance.service_times[index] = parse_number(row[3], line_number)?;
+            instance.demands[index] = Some(parse_number(row[4], line_number)?);
+        }
+
+        instance.validate_graph()?;
+        instance.validate_vehicle_routing()?;
+        Ok(instance)
+    }
+
+    fn parse_keyword(&mut self, line: &str, line_number: usize) -> Result<Section, ParseError> {
+        let (keyword, value) = match line.split_once(':') {
+            Some((keyword, value)) => (keyword.trim(), Some(value.trim())),
+            None => (line, None),
+        };
+
+        let required_value = || {
+            value.filter(|value| !value.is_empty()).ok_or_else(|| {
+                ParseError::at(
+                    line_number,
+                    ParseErrorKind::MissingValue(keyword.to_string()),
+                )
+            })
+        };
+
+        match keyword {
+            "NAME" => self.name = required_value()?.to_string(),
+            "COMMENT" => {
+                if !self.comment.is_empty() {
+                    self.comment.push(' ');
+                }
+                self.comment.push_str(value.unwrap_or_default());
+            }
+            "TYPE" => self.type_ = required_value()?.to_string(),
+            "DIMENSION" => {
+                let dimension = parse_number::<usize>(required_value()?, line_number)?;
+                self.dimension = Some(dimension);
+                self.node_coordinates = (0..dimension).map(|_| None).collect();
+                self.demands = vec![None; dimension];
+            }
+            "CAPACITY" => self.capacity = Some(parse_number(required_value()?, line_number)?),
+            "DISTANCE" => self.max_distance = Some(parse_number(required_value()?, line_number)?),
+            "SERVICE_TIME" => {
+                self.service_time = Some(parse_number(required_value()?, line_number)?)
+            }
+            "VEHICLES" => self.vehicles = Some(parse_number(required_value()?, line_number)?),
+            "EDGE_WEIGHT_TYPE" => {
+                self.edge_weight_type = required_value()?.to_string();
+                self.distance_function = DistanceFunction::try_from(self.edge_weight_type.as_str())
+                    .map_err(|type_| {
+                        ParseError::at(
+                            line_number,
+                            ParseErrorKind::UnsupportedEdgeWeightType(type_),
+                        )
+                    })?;
+            }
+            "EDGE_WEIGHT_FORMAT" => self.edge_weight_format = required_value()?.to_string(),
+            "NODE_COORD_TYPE" => self.node_coord_type = required_value()?.to_string(),
+            "DISPLAY_DATA_TYPE" => self.display_data_type = required_value()?.to_string(),
+            "EDGE_DATA_FORMAT" => {}
+            "EOF" => return Ok(Section::Finished),
+            "NODE_COORD_SECTION" => {
+                self.require_dimension(line_number)?;
+                self.has_node_coord_section = true;
+                return Ok(Section::NodeCoordinates);
+            }
+            "DEMAND_SECTION" => {
+                self.require_dimension(line_number)?;
+                self.has_demand_section = true;
+                return Ok(Section::Demands);
+            }
+            "DEPOT_SECTION" => {
+                self.require_dimension(line_number)?;
+                return Ok(Section::Depots);
+            }
+            "EDGE_WEIGHT_SECTION" => {
+                self.require_dimension(line_number)?;
+                return Ok(Section::EdgeWeights);
+            }
+            "DISPLAY_DATA_SECTION" | "FIXED_EDGES_SECTION" | "TOUR_SECTION" => {
+                return Ok(Section::Skipped)
+            }
+            _ => {
+                return Err(ParseError::at(
+                    line_number,
+                    ParseErrorKind::UnknownKeyword(keyword.to_string()),
+                ))
+            }
+        };
+
+        Ok(Section::Specification)
+    }
+
+    fn require_dimension(&self, line_number: usize) -> Result<usize, ParseError> {
+        self.dimension
+            .ok_or_else(|| ParseError::at(line_number, ParseErrorKind::MissingDimension))
+    }
+
+    fn node_index(&self, node: &str, line_number: usize) -> Result<usize, ParseError> {
+        let dimension = self.require_dimension(line_number)?;
+        let node = parse_number::<usize>(node, line_number)?;
+        if node == 0 || node > dimension {
+            return Err(ParseError::at(
+                line_number,
+                ParseErrorKind::NodeOutOfRange(node),
+            ));
+        }
+        Ok(node - 1)
+    }
+
+    fn parse_node_coordinates(&mut self, line: &str, line_number: usize) -> Result<(), ParseError> {
+        let row = line.split_whitespace().collect::<Vec<&str>>();
+        if row.len() != 3 {
+            return Err(ParseError::at(
+                line_number,
+                ParseErrorKind::MalformedRow(line.to_string()),
+            ));
+        }
+
+        let index = self.node_index(row[0], line_number)?;
+        let coordinates = Coordinates {
+            x: parse_number(row[1], line_number)?,
+            y: parse_number(row[2], line_number)?,
+        };
+
+        match self.node_coordinates[index].replace(coordinates) {
+            Some(_) => Err(ParseError::at(
+                line_number,
+                ParseErrorKind::DuplicateNode(index + 1),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_demand(&mut self, line: &str, line_number: usize) -> Result<(), ParseError> {
+        let row = line.split_whitespace().collect::<Vec<&str>>();
+        if row.len() != 2 {
+            return Err(ParseError::at(
+                line_number,
+                ParseErrorKind::MalformedRow(line.to_string()),
+            ));
+        }
+
+        let index = self.node_index(row[0], line_number)?;
+        let demand = parse_number(row[1], line_number)?;
+
+        match self.demands[index].replace(demand) {
+            Some(_) => Err(ParseError::at(
+                line_number,
+                ParseErrorKind::DuplicateNode(index + 1),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_depot(&mut self, line: &str, line_number: usize) -> Result<Section, ParseError> {
+        let mut row = line.split_whitespace();
+        let depot = row.next().unwrap_or_default();
+        if row.next().is_some() {
+            return Err(ParseError::at(
+                line_number,
+                ParseErrorKind::MalformedRow(line.to_string()),
+            ));
+        }
+
+        if depot == "-1" {
+            return Ok(Section::Specification);
+        }
+
+        let index = self.node_index(depot, line_number)?;
+        self.depots.push(index);
+        Ok(Section::Depots)
+    }
+
+    fn parse_edge_weights(&mut self, line: &str, line_number: usize) -> Result<(), ParseError> {
+        for weight in line.split_whitespace() {
+            self.raw_edge_weights
+                .push(parse_number(weight, line_number)?);
+        }
+        Ok(())
+    }
+
+    pub fn is_explicit(&self) -> bool {
+        self.distance_function == DistanceFunction::Explicit
+    }
+
+    fn build_edge_weight_matrix(&self) -> Result<Vec<Vec<Fitness>>, ParseError> {
+        let dimension = self.dimension.unwrap_or_default();
+
+        // Column-wise formats of a symmetric matrix list the same values as the mirrored row-wise ones
+        let cells: Vec<(usize, usize)> = match self.edge_weight_format.as_str() {
+            "FULL_MATRIX" => (0..dimension)
+                .flat_map(|i| (0..dimension).map(move |j| (i, j)))
+                .collect(),
+            "UPPER_ROW" | "LOWER_COL" => (0..dimension)
+                .flat_map(|i| (i + 1..dimension).map(move |j| (i, j)))
+                .collect(),
+            "LOWER_ROW" | "UPPER_COL" => (0..dimension)
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .collect(),
+            "UPPER_DIAG_ROW" | "LOWER_DIAG_COL" => (0..dimension)
+                .flat_map(|i| (i..dimension).map(move |j| (i, j)))
+                .collect(),
+            "LOWER_DIAG_ROW" | "UPPER_DIAG_COL" => (0..dimension)
+                .flat_map(|i| (0..=i).map(move |j| (i, j)))
+                .collect(),
+            format => {
+                return Err(ParseError::global(
+                    ParseErrorKind::UnsupportedEdgeWeightFormat(format.to_string()),
+                ))
+            }
+        };
+
+        if cells.len() != self.raw_edge_weights.len() {
+            return Err(ParseError::global(ParseErrorKind::EdgeWeightCount {
+                expected: cells.len(),
+                found: self.raw_edge_weights.len(),
+            }));
+        }
+
+        let symmetric = self.edge_weight_format != "FULL_MATRIX";
+        let mut matrix = vec![vec![Fitness::default(); dimension]; dimension];
+        for ((i, j), weight) in cells.into_iter().zip(&self.raw_edge_weights) {
+            matrix[i][j] = *weight;
+            if symmetric {
+                matrix[j][i] = *weight;
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    fn validate_graph(&self) -> Result<(), ParseError> {
+        let dimension = self
+            .dimension
+            .ok_or(ParseError::global(ParseErrorKind::MissingDimension))?;
+
+        if self.is_explicit() {
+            if self.raw_edge_weights.is_empty() {
+                return Err(ParseError::global(ParseErrorKind::MissingSection(
+                    "EDGE_WEIGHT_SECTION",
+                )));
+            }
+        } else if !self.has_node_coord_section {
+            return Err(ParseError::global(ParseErrorKind::MissingSection(
+                "NODE_COORD_SECTION",
+            )));
+        }
+
+        let coordinates = self.node_coordinates.iter().flatten().count();
+        if self.has_node_coord_section && coordinates != dimension {
+            return Err(ParseError::global(ParseErrorKind::DimensionMismatch {
+                section: "NODE_COORD_SECTION",
+                expected: dimension,
+                found: coordinates,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn validate_vehicle_routing(&self) -> Result<(), ParseError> {
+        let dimension = self.dimension.unwrap_or_default();
+
+        if !self.has_demand_section {
+            return Err(ParseError::global(ParseErrorKind::MissingSection(
+                "DEMAND_SECTION",
+            )));
+        }
+        let demands = self.demands.iter().flatten().count();
+        if demands != dimension {
+            return Err(ParseError::global(ParseErrorKind::DimensionMismatch {
+                section: "DEMAND_SECTION",
+                expected: dimension,
+                found: demands,
+            }));
+        }
+
+        if self.depots.is_empty() {
+            return Err(ParseError::global(ParseErrorKind::MissingSection(
+                "DEPOT_SECTION",
+            )));
+        }
+
+        if self.capacity.is_none() {
+            return Err(ParseError::global(ParseErrorKind::MissingCapacity));
+        }
+
+        Ok(())
+    }
+
+    // Every customer takes SERVICE_TIME to serve and no route may last longer than DISTANCE
+    fn apply_route_limits(&mut self) {
+        if let Some(service_time) = self.service_time {
+            self.service_times = (0..self.dimension.unwrap_or_default())
+                .map(|node| match self.depots.contains(&node) {
+                    true => Fitness::default(),
+                    false => service_time,
+                })
+                .collect();
+        }
+
+        if let Some(max_distance) = self.max_distance {
+            self.depot_fleets = self
+                .depots
+                .iter()
+                .map(|_| DepotFleet {
+                    capacity: self.capacity.unwrap_or_default(),
+                    max_duration: Some(max_distance),
+                    vehicles: None,
+                })
+                .collect();
+        }
+    }
+}
+
+fn starts_with_number(line: &str) -> bool {
+    line.starts_with(|first: char| {
+        first.is_ascii_digit() || first == '-' || first == '+' || first == '.'
+    })
+}
+
+fn parse_number<T: FromStr>(value: &str, line_number: usize) -> Result<T, ParseError> {
+    value.parse::<T>().map_err(|_| {
+        ParseError::at(
+            line_number,
+            ParseErrorKind::InvalidNumber(value.to_string()),
+        )
+    })
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::fs::{read_to_string, write};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::individual::{Fitness, Gene, VecIndividual};
+
+use super::{
+    decoder::{DeliveryMode, DepotAssignment},
+    CVRProblem,
+};
+
+// Solution in the CVRPLIB .sol format, customers are numbered from 1 and the depot is implicit
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SolutionFile {
+    pub routes: Vec<Vec<usize>>,
+    pub vehicles: Option<usize>,
+    pub cost: Option<Fitness>,
+}
+
+impl SolutionFile {
+    pub fn parse(content: &str) -> Result<SolutionFile> {
+        let mut solution = SolutionFile::default();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            let line_number = index + 1;
+
+            if line.starts_with("Route") {
+                let (_, stops) = line
+                    .split_once(':')
+                    .with_context(|| format!("line {}: route without ':'", line_number))?;
+                let route = stops
+                    .split_whitespace()
+                    .map(|stop| {
+                        stop.parse::<usize>().map_err(|_| {
+                            anyhow!("line {}: invalid customer '{}'", line_number, stop)
+                        })
+                    })
+                    .collect::<Result<Vec<usize>>>()?;
+                solution.routes.push(route);
+            } else if let Some(vehicles) = line.strip_prefix("Vehicles") {
+                let vehicles = vehicles.trim_start_matches(':').trim();
+                solution.vehicles = Some(vehicles.parse::<usize>().map_err(|_| {
+                    anyhow!("line {}: invalid vehicles '{}'", line_number, vehicles)
+                })?);
+            } else if let Some(cost) = line.strip_prefix("Cost") {
+                let cost = cost.trim_start_matches(':').trim();
+                solution.cost = Some(
+                    cost.parse::<Fitness>()
+                        .map_err(|_| anyhow!("line {}: invalid cost '{}'", line_number, cost))?,
+                );
+            }
+        }
+
+        if solution.routes.is_empty() {
+            return Err(anyhow!("solution file contains no routes"));
+        }
+
+        Ok(solution)
+    }
+}
+
+impl Display for SolutionFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, route) in self.routes.iter().enumerate() {
+            let stops = route
+                .iter()
+                .map(|stop| stop.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(f, "Route #{}: {}", index + 1, stops)?;
+        }
+
+        if let Some(vehicles) = self.vehicles {
+            writeln!(f, "Vehicles {}", vehicles)?;
+        }
+
+        if let Some(cost) = self.cost {
+            writeln!(f, "Cost {}", cost)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl CVRProblem {
+    pub fn solution_file(&self, individual: &VecIndividual) -> Result<SolutionFile> {
+        let routes = self.decode(individual)?;
+
+        Ok(SolutionFile {
+            vehicles: Some(routes.len()),
+            cost: Some(routes.iter().map(|route| route.length).sum()),
+            routes: routes
+                .iter()
+                .map(|route| {
+                    route
+                        .stops
+                        .iter()
+                        .map(|stop| self.customer_number(stop))
+                        .collect::<Result<Vec<usize>>>()
+                })
+                .collect::<Result<Vec<Vec<usize>>>>()?,
+        })
+    }
+
+    // Concatenates the routes into a giant tour, evaluation may split it differently
+    // when consecutive routes fit into a single vehicle
+    pub fn individual_from_solution(&self, solution: &SolutionFile) -> Result<VecIndividual> {
+        let mut customers: Vec<usize> = solution.routes.iter().flatten().copied().collect();
+        // Customers with split deliveries appear in several routes, the first visit is kept
+        if self.delivery_mode == DeliveryMode::Split {
+            let mut seen = HashSet::new();
+            customers.retain(|customer| seen.insert(*customer));
+        }
+
+        let genes = customers
+            .iter()
+            .map(|customer| {
+                self.stops
+                    .get(customer.wrapping_sub(1))
+                    .copied()
+                    .with_context(|| format!("Customer {} does not exist", customer))
+            })
+            .collect::<Result<Vec<Gene>>>()?;
+
+        let mut visited = genes.clone();
+        visited.sort();
+        visited.dedup();
+        if visited.len() != genes.len() || visited.len() != self.stops.len() {
+            return Err(anyhow!("Solution has to visit every customer exactly once"));
+        }
+
+        // Depot genes are kept together, so every customer is served from the first depot
+        // and the decoder is free to split the tour as it would for a single depot
+        let genes = match self.depot_assignment {
+            DepotAssignment::Closest => genes,
+            DepotAssignment::Encoded => [&self.depots[..1], &genes, &self.depots[1..]].concat(),
+        };
+
+        Ok(VecIndividual::from(genes))
+    }
+
+    pub fn save_solution(&self, path: &str, individual: &VecIndividual) -> Result<()> {
+        write(path, self.solution_file(individual)?.to_string())
+            .with_context(|| format!("Failed to write {}", path))
+    }
+
+    pub fn load_solution(&self, path: &str) -> Result<VecIndividual> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        self.individual_from_solution(&SolutionFile::parse(&content)?)
+    }
+
+    // CVRPLIB numbers customers from 1, skipping the depot
+    fn customer_number(&self, stop: &Gene) -> Result<usize> {
+        self.stops
+            .iter()
+            .position(|customer| customer == stop)
+            .map(|position| position + 1)
+            .with_context(|| format!("Node {} is not a customer", stop))
+    }
+}
@@ -1,11 +1,32 @@
-use crate::individual::VecIndividual;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    individual::{moves::Move, Fitness, Individual, VecIndividual},
+    neighbor::{
+        InverseNeighborhoodOperator, NeighborOperator, RelocateNeighborhoodOperator,
+        SwapNeighborhoodOperator,
+    },
+    problem::Problem,
+};
+
+use super::{
+    best_known::BestKnownSolution,
+    decoder::{Decoder, DeliveryMode, DepotAssignment, Route},
+    distance::{DistanceFunction, DistancePrecision},
+    fleet::{DepotFleet, FleetLimit},
+    parser::ParseErrorKind,
+    solution_file::SolutionFile,
+    vehicle::{VehicleAssignment, VehicleType},
+    CVRProblem, Coordinates,
+};
 
 #[test]
 fn random_index_test() {
     let individual = VecIndividual::from(&vec![1, 2, 3, 4, 5, 6]);
+    let mut rng = StdRng::seed_from_u64(0);
 
     for _ in 0..100 {
-        let (start, end) = individual.random_gene_range_indexes();
+        let (start, end) = individual.random_gene_range_indexes(&mut rng);
         assert!(start < end, "start index is smaller than end index");
         assert!(
             start < individual.number_of_genes() - 1,
@@ -17,3 +38,728 @@ fn random_index_test() {
         );
     }
 }
+
+#[test]
+fn parse_toy_instance() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+
+    assert_eq!(problem.name(), "toy.vrp");
+    assert_eq!(problem.capacity(), 30);
+    assert_eq!(problem.stops(), &vec![1, 2, 3, 4, 5]);
+    assert_eq!(problem.demands(&2).unwrap(), 18);
+}
+
+#[test]
+fn parse_tolerates_tabs_and_multiple_spaces() {
+    let content = "NAME:\ttabs\nTYPE : CVRP\nDIMENSION :  3\nCAPACITY\t:\t10\n\
+                   NODE_COORD_SECTION\n1\t0   0\n  2  3\t4\n3 6 8\n\
+                   DEMAND_SECTION\n1   0\n2\t5\n3 5\nDEPOT_SECTION\n 1\n -1\nEOF\n";
+
+    let problem = CVRProblem::parse(content).unwrap();
+
+    assert_eq!(problem.name(), "tabs");
+    assert_eq!(problem.stops(), &vec![1, 2]);
+}
+
+#[test]
+fn parse_reports_line_of_malformed_value() {
+    let content = "NAME : broken\nDIMENSION : 2\nCAPACITY : 10\n\
+                   NODE_COORD_SECTION\n1 0 0\n2 x 4\n";
+
+    let err = CVRProblem::parse(content).unwrap_err();
+
+    assert_eq!(err.line, Some(6));
+    assert_eq!(err.kind, ParseErrorKind::InvalidNumber(String::from("x")));
+}
+
+#[test]
+fn parse_rejects_unknown_keywords() {
+    let content = "NAME : broken\nDIMENSION : 2\nSOMETHING_SECTION\n";
+
+    let err = CVRProblem::parse(content).unwrap_err();
+
+    assert_eq!(err.line, Some(3));
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::UnknownKeyword(String::from("SOMETHING_SECTION"))
+    );
+}
+
+#[test]
+fn parse_route_limits() {
+    let content = "NAME : limited\nTYPE : CVRP\nDIMENSION : 3\nCAPACITY : 10\n\
+                   DISTANCE : 30\nSERVICE_TIME : 5\nVEHICLES : 1\n\
+                   NODE_COORD_SECTION\n1 0 0\n2 3 4\n3 6 8\n\
+                   DEMAND_SECTION\n1 0\n2 5\n3 5\nDEPOT_SECTION\n1\n-1\nEOF\n";
+
+    let problem = CVRProblem::parse(content).unwrap();
+
+    assert_eq!(problem.fleet_limit(), FleetLimit::Hard(1));
+    assert_eq!(problem.service_time(&0), 0.0);
+    assert_eq!(problem.service_time(&2), 5.0);
+    assert_eq!(
+        problem.depot_fleets(),
+        &vec![DepotFleet {
+            capacity: 10,
+            max_duration: Some(30.0),
+            vehicles: None,
+        }]
+    );
+
+    // Travelling 20 and serving both customers takes exactly the allowed 30
+    let individual = VecIndividual::from(vec![1, 2]);
+    assert_eq!(problem.total_cost(&individual).unwrap(), 20.0);
+}
+
+#[test]
+fn demands_above_capacity_load_and_decode_with_split_deliveries() {
+    let content = "NAME : heavy\nDIMENSION : 3\nCAPACITY : 10\n\
+                   NODE_COORD_SECTION\n1 0 0\n2 3 4\n3 6 8\n\
+                   DEMAND_SECTION\n1 0\n2 25\n3 5\nDEPOT_SECTION\n1\n-1\nEOF\n";
+    let mut problem = CVRProblem::parse(content).unwrap();
+    let individual = VecIndividual::from(vec![1, 2]);
+
+    assert!(problem.validate_demands().is_err());
+    problem.set_delivery_mode(DeliveryMode::Split).unwrap();
+
+    let routes = problem.decode(&individual).unwrap();
+    let deliveries: Vec<Vec<u16>> = routes.iter().map(|r| r.deliveries.clone()).collect();
+    assert_eq!(deliveries, vec![vec![10], vec![10], vec![5, 5]]);
+}
+
+#[test]
+fn loads_near_the_demand_limit_do_not_overflow() {
+    let content = "NAME : heavy\nDIMENSION : 3\nCAPACITY : 60000\n\
+                   NODE_COORD_SECTION\n1 0 0\n2 3 4\n3 6 8\n\
+                   DEMAND_SECTION\n1 0\n2 40000\n3 40000\nDEPOT_SECTION\n1\n-1\nEOF\n";
+    let mut problem = CVRProblem::parse(content).unwrap();
+    let individual = VecIndividual::from(vec![1, 2]);
+
+    for decoder in [Decoder::Greedy, Decoder::OptimalSplit] {
+        problem.set_decoder(decoder);
+        assert_eq!(problem.decode(&individual).unwrap().len(), 2);
+    }
+
+    problem.set_decoder(Decoder::Greedy);
+    let values = problem
+        .eval_moves(&individual, &[Move::Swap(0, 1)])
+        .unwrap();
+    assert_eq!(values, vec![problem.eval(&individual).unwrap()]);
+}
+
+#[test]
+fn parse_validates_dimension() {
+    let content = "NAME : short\nDIMENSION : 3\nCAPACITY : 10\n\
+                   NODE_COORD_SECTION\n1 0 0\n2 3 4\n\
+                   DEMAND_SECTION\n1 0\n2 5\nDEPOT_SECTION\n1\n-1\nEOF\n";
+
+    let err = CVRProblem::parse(content).unwrap_err();
+
+    assert_eq!(err.line, None);
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::DimensionMismatch {
+            section: "NODE_COORD_SECTION",
+            expected: 3,
+            found: 2,
+        }
+    );
+}
+
+#[test]
+fn parse_bundled_instances() {
+    let instances = [
+        include_str!("../problem-instances/A-n32-k5.txt"),
+        include_str!("../problem-instances/A-n37-k5.txt"),
+        include_str!("../problem-instances/A-n39-k5.txt"),
+        include_str!("../problem-instances/A-n45-k7.txt"),
+        include_str!("../problem-instances/A-n48-k7.txt"),
+        include_str!("../problem-instances/A-n54-k7.txt"),
+        include_str!("../problem-instances/A-n60-k9.txt"),
+    ];
+
+    for instance in instances {
+        let problem = CVRProblem::parse(instance).unwrap();
+        assert_eq!(problem.stops().len() + 1, problem.dimension());
+    }
+}
+
+#[test]
+fn parse_explicit_lower_row_weights() {
+    let content = "NAME : explicit\nTYPE : CVRP\nDIMENSION : 4\nCAPACITY : 10\n\
+                   EDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : LOWER_ROW\n\
+                   EDGE_WEIGHT_SECTION\n 1\n 2 3\n 4 5\n 6\n\
+                   DEMAND_SECTION\n1 0\n2 1\n3 1\n4 1\nDEPOT_SECTION\n1\n-1\nEOF\n";
+
+    let problem = CVRProblem::parse(content).unwrap();
+
+    assert_eq!(problem.distance(&1, &0).unwrap(), 1.0);
+    assert_eq!(problem.distance(&0, &2).unwrap(), 2.0);
+    assert_eq!(problem.distance(&3, &2).unwrap(), 6.0);
+    assert_eq!(problem.distance(&2, &3).unwrap(), 6.0);
+    assert_eq!(problem.distance(&3, &3).unwrap(), 0.0);
+}
+
+#[test]
+fn parse_explicit_full_matrix_and_diagonal_formats() {
+    let full = "NAME : full\nDIMENSION : 3\nCAPACITY : 10\n\
+                EDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : FULL_MATRIX\n\
+                EDGE_WEIGHT_SECTION\n0 1 2\n1 0 3\n2 4 0\n\
+                DEMAND_SECTION\n1 0\n2 1\n3 1\nDEPOT_SECTION\n1\n-1\nEOF\n";
+    let lower_diag = "NAME : diag\nDIMENSION : 3\nCAPACITY : 10\n\
+                      EDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : LOWER_DIAG_ROW\n\
+                      EDGE_WEIGHT_SECTION\n0 1 0 2 3 0\n\
+                      DEMAND_SECTION\n1 0\n2 1\n3 1\nDEPOT_SECTION\n1\n-1\nEOF\n";
+
+    let full = CVRProblem::parse(full).unwrap();
+    let lower_diag = CVRProblem::parse(lower_diag).unwrap();
+
+    assert_eq!(full.distance(&1, &2).unwrap(), 3.0);
+    assert_eq!(full.distance(&2, &1).unwrap(), 4.0);
+    assert_eq!(lower_diag.distance(&1, &2).unwrap(), 3.0);
+    assert_eq!(lower_diag.distance(&2, &0).unwrap(), 2.0);
+}
+
+#[test]
+fn parse_rejects_incomplete_edge_weight_section() {
+    let content = "NAME : short\nDIMENSION : 3\nCAPACITY : 10\n\
+                   EDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : UPPER_ROW\n\
+                   EDGE_WEIGHT_SECTION\n1 2\n\
+                   DEMAND_SECTION\n1 0\n2 1\n3 1\nDEPOT_SECTION\n1\n-1\nEOF\n";
+
+    let err = CVRProblem::parse(content).unwrap_err();
+
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::EdgeWeightCount {
+            expected: 3,
+            found: 2
+        }
+    );
+}
+
+#[test]
+fn tsplib_distance_rounding() {
+    let origin = Coordinates { x: 0.0, y: 0.0 };
+    let node = Coordinates { x: 1.0, y: 1.0 };
+    let far_node = Coordinates { x: 10.0, y: 0.0 };
+
+    let rounded = DistancePrecision::Rounded;
+    assert_eq!(
+        DistanceFunction::Euclidean2D.distance(&origin, &node, rounded),
+        1.0
+    );
+    assert_eq!(
+        DistanceFunction::Ceil2D.distance(&origin, &node, rounded),
+        2.0
+    );
+    assert_eq!(
+        DistanceFunction::Manhattan2D.distance(&origin, &node, rounded),
+        2.0
+    );
+    assert_eq!(
+        DistanceFunction::PseudoEuclidean.distance(&origin, &far_node, rounded),
+        4.0
+    );
+    assert_eq!(
+        DistanceFunction::Euclidean2D.distance(&origin, &node, DistancePrecision::Exact),
+        2f64.sqrt()
+    );
+}
+
+#[test]
+fn geographical_distance() {
+    // Nodes 1 and 2 of TSPLIB burma14
+    let node_a = Coordinates { x: 16.47, y: 96.10 };
+    let node_b = Coordinates { x: 16.47, y: 94.44 };
+
+    let distance =
+        DistanceFunction::Geographical.distance(&node_a, &node_b, DistancePrecision::Rounded);
+
+    assert_eq!(distance, 153.0);
+}
+
+#[test]
+fn distance_precision_is_configurable() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+
+    let exact = problem.distance(&0, &3).unwrap();
+    problem.set_distance_precision(DistancePrecision::Rounded);
+    let rounded = problem.distance(&0, &3).unwrap();
+
+    assert!((exact - 17.492855).abs() < 1e-4);
+    assert_eq!(rounded, 17.0);
+}
+
+#[test]
+fn parse_rejects_unsupported_edge_weight_type() {
+    let content = "NAME : broken\nEDGE_WEIGHT_TYPE : XRAY1\n";
+
+    let err = CVRProblem::parse(content).unwrap_err();
+
+    assert_eq!(err.line, Some(2));
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::UnsupportedEdgeWeightType(String::from("XRAY1"))
+    );
+}
+
+#[test]
+fn parse_wide_signed_and_fractional_coordinates() {
+    let content = "NAME : wide\nDIMENSION : 3\nCAPACITY : 10\nEDGE_WEIGHT_TYPE : EUC_2D\n\
+                   NODE_COORD_SECTION\n1 -500.5 0\n2 1000 1e3\n3 40000 -40000\n\
+                   DEMAND_SECTION\n1 0\n2 1\n3 1\nDEPOT_SECTION\n1\n-1\nEOF\n";
+
+    let problem = CVRProblem::parse(content).unwrap();
+
+    let expected = (1500.5f64.powi(2) + 1000f64.powi(2)).sqrt();
+    assert!((problem.distance(&0, &1).unwrap() - expected).abs() < 1e-2);
+    assert!(problem.distance(&1, &2).unwrap() > 56_000.0);
+}
+
+#[test]
+fn best_known_from_comment() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/A-n37-k5.txt")).unwrap();
+
+    let best_known = problem.best_known().unwrap();
+
+    assert_eq!(best_known.vehicles, Some(5));
+    assert_eq!(best_known.cost, Some(669.0));
+    assert!((best_known.gap(702.45).unwrap() - 5.0).abs() < 1e-3);
+}
+
+#[test]
+fn best_known_vehicles_from_name() {
+    let best_known = BestKnownSolution::from_instance("X-n101-k25", "Generated by Uchoa et al.");
+
+    assert_eq!(best_known.unwrap().vehicles, Some(25));
+    assert_eq!(
+        BestKnownSolution::from_instance("toy.vrp", "toy instance"),
+        None
+    );
+}
+
+#[test]
+fn best_known_from_solution_file() {
+    let directory = std::env::temp_dir().join("cvrp-best-known");
+    std::fs::create_dir_all(&directory).unwrap();
+    let instance = directory.join("toy.vrp");
+    std::fs::write(&instance, include_str!("../problem-instances/test.txt")).unwrap();
+    std::fs::write(
+        directory.join("toy.sol"),
+        "Route #1: 1 3 4\nRoute #2: 2\nRoute #3: 5\nCost 250\n",
+    )
+    .unwrap();
+
+    let problem = CVRProblem::from_file(instance.to_str().unwrap()).unwrap();
+
+    let best_known = problem.best_known().unwrap();
+    assert_eq!(best_known.vehicles, Some(3));
+    assert_eq!(best_known.cost, Some(250.0));
+    assert_eq!(best_known.routes, vec![vec![1, 3, 4], vec![2], vec![5]]);
+}
+
+#[test]
+fn solution_file_round_trip() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    let individual = VecIndividual::from(vec![1, 2, 3, 4, 5]);
+
+    let solution = problem.solution_file(&individual).unwrap();
+    let serialized = problem.serialize_indiviual(&individual);
+    let parsed = SolutionFile::parse(&serialized).unwrap();
+
+    assert_eq!(solution.routes, vec![vec![1], vec![2, 3], vec![4, 5]]);
+    assert!(serialized.starts_with("Route #1: 1\nRoute #2: 2 3\nRoute #3: 4 5\nVehicles 3\nCost "));
+    assert_eq!(parsed, solution);
+    assert_eq!(
+        problem.individual_from_solution(&parsed).unwrap(),
+        individual
+    );
+}
+
+#[test]
+fn solution_file_rejects_missing_customers() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    let solution = SolutionFile::parse("Route #1: 1 2\nRoute #2: 3 3\nCost 10\n").unwrap();
+
+    assert!(problem.individual_from_solution(&solution).is_err());
+}
+
+#[test]
+fn decode_splits_routes_by_capacity() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    let individual = VecIndividual::from(vec![1, 2, 3, 4, 5]);
+
+    let routes = problem.decode(&individual).unwrap();
+
+    let stops = routes
+        .iter()
+        .map(|route| route.stops.clone())
+        .collect::<Vec<Vec<u16>>>();
+    let loads = routes.iter().map(|route| route.load).collect::<Vec<u16>>();
+    assert_eq!(stops, vec![vec![1], vec![2, 3], vec![4, 5]]);
+    assert_eq!(loads, vec![16, 19, 21]);
+    assert_eq!(
+        routes[0].length,
+        problem.distance(&0, &1).unwrap() + problem.distance(&1, &0).unwrap()
+    );
+
+    let total: Fitness = routes.iter().map(|route| route.length).sum();
+    assert_eq!(problem.eval(&individual).unwrap(), total);
+}
+
+#[test]
+fn optimal_split_is_never_worse_than_greedy() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/A-n32-k5.txt")).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..50 {
+        let individual = problem.random_individual(&mut rng);
+
+        problem.set_decoder(Decoder::Greedy);
+        let greedy = problem.total_distance(&individual).unwrap();
+        problem.set_decoder(Decoder::OptimalSplit);
+        let routes = problem.decode(&individual).unwrap();
+        let split: Fitness = routes.iter().map(|route| route.length).sum();
+
+        assert!(split <= greedy + 1e-3);
+        assert!(routes.iter().all(|route| route.load <= problem.capacity()));
+        let genes = routes
+            .iter()
+            .flat_map(|route| route.stops.clone())
+            .collect::<Vec<u16>>();
+        assert_eq!(&genes, individual.genes());
+    }
+}
+
+#[test]
+fn fleet_limit_penalizes_extra_vehicles() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    let individual = VecIndividual::from(vec![1, 2, 3, 4, 5]);
+    let distance = problem.total_distance(&individual).unwrap();
+
+    problem.set_fleet_limit(FleetLimit::Penalized {
+        vehicles: 2,
+        penalty: 100.0,
+    });
+    assert_eq!(problem.total_cost(&individual).unwrap(), distance + 100.0);
+
+    problem.set_fleet_limit(FleetLimit::Hard(2));
+    assert_eq!(problem.total_cost(&individual).unwrap(), Fitness::INFINITY);
+    assert_eq!(problem.eval(&individual).unwrap(), Fitness::INFINITY);
+
+    problem.set_fleet_limit(FleetLimit::Hard(3));
+    assert_eq!(problem.total_cost(&individual).unwrap(), distance);
+}
+
+#[test]
+fn optimal_split_respects_hard_fleet_limit() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    // Demands 16, 1, 13, 18, 8 fit into two routes only as [1, 3, 4] and [2, 5]
+    let individual = VecIndividual::from(vec![1, 3, 4, 2, 5]);
+    problem.set_decoder(Decoder::OptimalSplit);
+
+    problem.set_fleet_limit(FleetLimit::Hard(2));
+    let routes = problem.decode(&individual).unwrap();
+
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].stops, vec![1, 3, 4]);
+    assert!(problem.total_cost(&individual).unwrap() < Fitness::INFINITY);
+
+    problem.set_fleet_limit(FleetLimit::Hard(1));
+    assert_eq!(problem.total_cost(&individual).unwrap(), Fitness::INFINITY);
+}
+
+// Customers 0..4 lie in two clusters, each next to one of the depots 4 and 5,
+// which have a single vehicle each
+const CORDEAU_INSTANCE: &str = "2 1 4 2
+0 20
+30 15
+1 1 0 0 12 1 4 1 2 4 8
+2 2 0 0 5 1 4 1 2 4 8
+3 101 0 0 5 1 4 1 2 4 8
+4 102 0 0 5 1 4 1 2 4 8
+5 0 0 0 0
+6 100 0 0 0
+";
+
+#[test]
+fn parse_cordeau_instance() {
+    let problem = CVRProblem::parse_cordeau(CORDEAU_INSTANCE).unwrap();
+
+    assert_eq!(problem.dimension(), 6);
+    assert_eq!(problem.capacity(), 20);
+    assert_eq!(problem.stops(), &vec![0, 1, 2, 3]);
+    assert_eq!(problem.depots(), &vec![4, 5]);
+    assert_eq!(problem.demands(&0).unwrap(), 12);
+    assert_eq!(problem.closest_depot(&3).unwrap(), 5);
+    assert_eq!(
+        problem.depot_fleets(),
+        &vec![
+            DepotFleet {
+                capacity: 20,
+                max_duration: None,
+                vehicles: Some(1),
+            },
+            DepotFleet {
+                capacity: 15,
+                max_duration: Some(30.0),
+                vehicles: Some(1),
+            },
+        ]
+    );
+
+    let error = CVRProblem::parse_cordeau("2 2 4 2\n0 20\n0 20\n1 1 0 0 5\n").unwrap_err();
+    assert!(matches!(
+        error.kind,
+        ParseErrorKind::DimensionMismatch { .. }
+    ));
+    for truncated in ["", "2 2 4 2\n0 20\n"] {
+        let error = CVRProblem::parse_cordeau(truncated).unwrap_err();
+        assert!(matches!(error.kind, ParseErrorKind::UnexpectedEnd(_)));
+    }
+}
+
+#[test]
+fn depot_fleets_limit_the_routes() {
+    let mut problem = CVRProblem::parse_cordeau(CORDEAU_INSTANCE).unwrap();
+    problem.set_depot_assignment(DepotAssignment::Encoded);
+
+    let feasible = VecIndividual::from(vec![4, 0, 1, 5, 2, 3]);
+    assert!(problem.total_cost(&feasible).unwrap() < Fitness::INFINITY);
+
+    // Depot 5 is too far from customer 0 for its duration limit
+    let too_long = VecIndividual::from(vec![5, 0, 4, 1, 2, 3]);
+    assert_eq!(problem.total_cost(&too_long).unwrap(), Fitness::INFINITY);
+
+    // All customers need two routes, but depot 4 has a single vehicle
+    let too_many = VecIndividual::from(vec![4, 0, 1, 2, 3, 5]);
+    assert_eq!(problem.decode(&too_many).unwrap().len(), 2);
+    assert_eq!(problem.total_cost(&too_many).unwrap(), Fitness::INFINITY);
+}
+
+#[test]
+fn routes_start_at_the_closest_depot() {
+    let mut problem = CVRProblem::parse_cordeau(CORDEAU_INSTANCE).unwrap();
+    problem.set_decoder(Decoder::OptimalSplit);
+    let routes = problem
+        .decode(&VecIndividual::from(vec![0, 1, 2, 3]))
+        .unwrap();
+
+    assert_eq!(routes.len(), 2);
+    assert_eq!((routes[0].depot, routes[1].depot), (4, 5));
+    assert_eq!(
+        problem
+            .total_distance(&VecIndividual::from(vec![0, 1, 2, 3]))
+            .unwrap(),
+        8.0
+    );
+}
+
+#[test]
+fn encoded_depots_assign_the_following_customers() {
+    let mut problem = CVRProblem::parse_cordeau(CORDEAU_INSTANCE).unwrap();
+    problem.set_depot_assignment(DepotAssignment::Encoded);
+
+    // Customers of the second cluster wrap around to the leading depot 5
+    let routes = problem
+        .decode(&VecIndividual::from(vec![2, 4, 0, 1, 5, 3]))
+        .unwrap();
+    let assignment: Vec<(u16, Vec<u16>)> = routes
+        .into_iter()
+        .map(|route| (route.depot, route.stops))
+        .collect();
+    assert_eq!(assignment, vec![(4, vec![0, 1]), (5, vec![3, 2])]);
+
+    let individual = problem.random_individual(&mut StdRng::seed_from_u64(0));
+    let mut genes = individual.genes().clone();
+    genes.sort();
+    assert_eq!(genes, vec![0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn heterogeneous_fleet_adds_vehicle_costs() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    let individual = VecIndividual::from(vec![1, 2, 3, 4, 5]);
+    let distance = problem.total_distance(&individual).unwrap();
+    let vehicle_type = VehicleType {
+        capacity: 30,
+        fixed_cost: 100.0,
+        distance_cost: 2.0,
+        available: Some(3),
+    };
+
+    problem
+        .set_vehicle_types(vec![vehicle_type.clone()])
+        .unwrap();
+    assert_eq!(
+        problem.total_cost(&individual).unwrap(),
+        300.0 + 2.0 * distance
+    );
+
+    problem
+        .set_vehicle_types(vec![VehicleType {
+            available: Some(2),
+            ..vehicle_type
+        }])
+        .unwrap();
+    assert_eq!(problem.total_cost(&individual).unwrap(), Fitness::INFINITY);
+}
+
+#[test]
+fn optimal_vehicle_assignment_beats_greedy() {
+    let route = |load, length| Route {
+        depot: 0,
+        stops: Vec::new(),
+        deliveries: vec![load],
+        load,
+        length,
+    };
+    let vehicle_type = |distance_cost| VehicleType {
+        capacity: 30,
+        fixed_cost: 0.0,
+        distance_cost,
+        available: Some(1),
+    };
+    let vehicle_types = vec![vehicle_type(1.0), vehicle_type(3.0)];
+    // The heavier route takes the cheap vehicle first, leaving the long route with the expensive one
+    let routes = vec![route(20, 1.0), route(10, 100.0)];
+
+    assert_eq!(
+        VehicleAssignment::Greedy.assign(&vehicle_types, &routes),
+        Some(vec![0, 1])
+    );
+    assert_eq!(
+        VehicleAssignment::Optimal.assign(&vehicle_types, &routes),
+        Some(vec![1, 0])
+    );
+
+    let small = VehicleType {
+        capacity: 15,
+        ..vehicle_type(1.0)
+    };
+    assert_eq!(
+        VehicleAssignment::Optimal.assign(&[small.clone(), small], &routes),
+        None
+    );
+}
+
+#[test]
+fn demands_above_capacity_need_split_deliveries() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/test.txt")).unwrap();
+    let small = vec![VehicleType {
+        capacity: 15,
+        fixed_cost: 0.0,
+        distance_cost: 1.0,
+        available: None,
+    }];
+    let individual = VecIndividual::from(vec![1, 2, 3, 4, 5]);
+
+    let error = problem.set_vehicle_types(small.clone()).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("exceeds the vehicle capacity 15"));
+    assert!(problem.vehicle_types().is_empty());
+
+    problem.set_delivery_mode(DeliveryMode::Split).unwrap();
+    problem.set_vehicle_types(small).unwrap();
+    assert!(problem.set_delivery_mode(DeliveryMode::Single).is_err());
+    assert_eq!(problem.delivery_mode(), DeliveryMode::Split);
+    let routes = problem.decode(&individual).unwrap();
+    let deliveries: Vec<Vec<(u16, u16)>> = routes
+        .iter()
+        .map(|route| {
+            route
+                .stops
+                .iter()
+                .copied()
+                .zip(route.deliveries.clone())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        deliveries,
+        vec![
+            vec![(1, 15)],
+            vec![(1, 1), (2, 14)],
+            vec![(2, 4), (3, 1), (4, 10)],
+            vec![(4, 3), (5, 8)],
+        ]
+    );
+    assert!(problem.eval(&individual).unwrap() > 0.0);
+
+    let solution = problem.solution_file(&individual).unwrap();
+    assert_eq!(
+        problem.individual_from_solution(&solution).unwrap(),
+        individual
+    );
+}
+
+#[test]
+fn moves_know_where_every_gene_ends_up() {
+    let genes: Vec<u16> = (0..8).collect();
+    let steps = [
+        Move::Swap(6, 1),
+        Move::TwoOpt(2, 5),
+        Move::Relocate { from: 1, to: 6 },
+        Move::Relocate { from: 7, to: 0 },
+        Move::Chain(vec![Move::Swap(0, 7), Move::Relocate { from: 3, to: 5 }]),
+    ];
+
+    for step in steps {
+        let mut moved = genes.clone();
+        step.apply(&mut moved);
+        let (first, last) = step.affected_range().unwrap();
+
+        for (index, gene) in moved.iter().enumerate() {
+            assert_eq!(step.gene_at(&genes, index), *gene, "{:?}", step);
+            if index < first || index > last {
+                assert_eq!(genes[index], *gene, "{:?}", step);
+            }
+        }
+    }
+}
+
+#[test]
+fn move_values_match_full_evaluation() {
+    let mut problem = CVRProblem::parse(include_str!("../problem-instances/A-n32-k5.txt")).unwrap();
+    let operators: Vec<Box<dyn NeighborOperator>> = vec![
+        Box::new(SwapNeighborhoodOperator::new(20)),
+        Box::new(InverseNeighborhoodOperator::new(20)),
+        Box::new(RelocateNeighborhoodOperator::new(20)),
+    ];
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for fleet_limit in [
+        FleetLimit::Unlimited,
+        FleetLimit::Penalized {
+            vehicles: 5,
+            penalty: 100.0,
+        },
+        FleetLimit::Hard(5),
+    ] {
+        problem.set_fleet_limit(fleet_limit);
+        assert!(problem.supports_move_delta());
+
+        for _ in 0..10 {
+            let individual = problem.random_individual(&mut rng);
+
+            for operator in &operators {
+                let steps = operator.get_moves(&individual, &mut rng);
+                let values = problem.eval_moves(&individual, &steps).unwrap();
+
+                for (step, value) in steps.iter().zip(values) {
+                    let mut neighbor = individual.clone();
+                    neighbor.apply_move(step);
+                    let expected = problem.eval(&neighbor).unwrap();
+                    // Infeasible neighbors under the hard limit are infinite on both sides
+                    assert!(
+                        value == expected || (value - expected).abs() < 1e-6,
+                        "{:?}",
+                        step
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::individual::Fitness;
+
+use super::{decoder::Route, Demand};
+
+// Kind of vehicle available in a heterogeneous fleet
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehicleType {
+    pub capacity: Demand,
+    // Paid once for every route served by a vehicle of this type
+    pub fixed_cost: Fitness,
+    pub distance_cost: Fitness,
+    // None for an unlimited number of vehicles
+    pub available: Option<usize>,
+}
+
+impl VehicleType {
+    pub fn route_cost(&self, route: &Route) -> Fitness {
+        self.fixed_cost + self.distance_cost * route.length
+    }
+}
+
+// How decoded routes are matched with the vehicle types
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum VehicleAssignment {
+    // Routes with the highest load pick the cheapest vehicle that is still available
+    #[default]
+    Greedy,
+    // Minimum cost assignment of routes to single vehicles, solved with the Hungarian method
+    Optimal,
+}
+
+impl VehicleAssignment {
+    // Index of the vehicle type serving every route, None when the fleet cannot serve all of them
+    pub fn assign(&self, vehicle_types: &[VehicleType], routes: &[Route]) -> Option<Vec<usize>> {
+        match self {
+            Self::Greedy => greedy_assignment(vehicle_types, routes),
+            Self::Optimal => optimal_assignment(vehicle_types, routes),
+        }
+    }
+}
+
+fn greedy_assignment(vehicle_types: &[VehicleType], routes: &[Route]) -> Option<Vec<usize>> {
+    let mut available: Vec<Option<usize>> = vehicle_types
+        .iter()
+        .map(|vehicle_type| vehicle_type.available)
+        .collect();
+    let mut order: Vec<usize> = (0..routes.len()).collect();
+    order.sort_by_key(|route| std::cmp::Reverse(routes[*route].load));
+
+    let mut assignment = vec![0; routes.len()];
+    for route in order {
+        let (vehicle_type, _) = vehicle_types
+            .iter()
+            .enumerate()
+            .filter(|(index, vehicle_type)| {
+                vehicle_type.capacity >= routes[route].load && available[*index] != Some(0)
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.route_cost(&routes[route])
+                    .total_cmp(&b.route_cost(&routes[route]))
+            })?;
+
+        if let Some(left) = &mut available[vehicle_type] {
+            *left -= 1;
+        }
+        assignment[route] = vehicle_type;
+    }
+
+    Some(assignment)
+}
+
+fn optimal_assignment(vehicle_types: &[VehicleType], routes: &[Route]) -> Option<Vec<usize>> {
+    // Every available vehicle becomes a column, unlimited types need at most one per route
+    let vehicles: Vec<usize> = vehicle_types
+        .iter()
+        .enumerate()
+        .flat_map(|(index, vehicle_type)| {
+            let count = vehicle_type
+                .available
+                .unwrap_or(routes.len())
+                .min(routes.len());
+            std::iter::repeat_n(index, count)
+        })
+        .collect();
+    if vehicles.len() < routes.len() {
+        return None;
+    }
+
+    let costs: Vec<Vec<Option<f64>>> = routes
+        .iter()
+        .map(|route| {
+            vehicles
+                .iter()
+                .map(|vehicle| {
+                    let vehicle_type = &vehicle_types[*vehicle];
+                    (vehicle_type.capacity >= route.load).then(|| vehicle_type.route_cost(route))
+                })
+                .collect()
+        })
+        .collect();
+
+    hungarian(&costs)?
+        .into_iter()
+        .map(|column| Some(vehicles[column]))
+        .collect()
+}
+
+// Hungarian method for a rows <= columns matrix, None entries are forbidden pairs.
+// Returns the column assigned to every row, None when no assignment avoids forbidden pairs.
+fn hungarian(costs: &[Vec<Option<f64>>]) -> Option<Vec<usize>> {
+    let rows = costs.len();
+    let columns = costs.first().map_or(0, |row| row.len());
+    // Larger than any feasible assignment, so forbidden pairs are only chosen when unavoidable
+    let forbidden = 1.0
+        + costs
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|cost| cost.abs())
+            .sum::<f64>();
+    let cost = |row: usize, column: usize| costs[row - 1][column - 1].unwrap_or(forbidden);
+
+    // 1-based potentials and matching, column 0 is a virtual starting column
+    let mut row_potential = vec![0f64; rows + 1];
+    let mut column_potential = vec![0f64; columns + 1];
+    let mut matched_row = vec![0usize; columns + 1];
+    let mut way = vec![0usize; columns + 1];
+
+    for row in 1..=rows {
+        matched_row[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f64::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+
+        while matched_row[column] != 0 {
+            used[column] = true;
+            let current_row = matched_row[column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+
+            for candidate in 1..=columns {
+                if used[candidate] {
+                    continue;
+                }
+                let slack = cost(current_row, candidate)
+                    - row_potential[current_row]
+                    - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    way[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next_column = candidate;
+                }
+            }
+
+            for candidate in 0..=columns {
+                if used[candidate] {
+                    row_potential[matched_row[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next_column;
+        }
+
+        while column != 0 {
+            let previous = way[column];
+            matched_row[column] = matched_row[previous];
+            column = previous;
+        }
+    }
+
+    let mut assignment = vec![0usize; rows];
+    for column in 1..=columns {
+        if matched_row[column] != 0 {
+            assignment[matched_row[column] - 1] = column - 1;
+        }
+    }
+
+    assignment
+        .iter()
+        .enumerate()
+        .all(|(row, column)| costs[row][*column].is_some())
+        .then_some(assignment)
+}
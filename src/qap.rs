@@ -0,0 +1,185 @@
+use crate::individual::{moves::Move, Fitness, Gene, Individual, VecIndividual};
+use crate::parsing::parse_number;
+use crate::problem::Problem;
+use anyhow::{anyhow, Context, Result};
+use rand::RngCore;
+use std::fs::read_to_string;
+
+#[cfg(test)]
+mod tests;
+
+pub type Cost = i64;
+
+// Quadratic assignment, gene i is the location of facility i.
+// The cost is the sum of flow(i, j) * distance(location(i), location(j)).
+#[derive(Debug, Default)]
+pub struct QAPProblem {
+    name: String,
+    size: usize,
+    flows: Vec<Vec<Cost>>,
+    distances: Vec<Vec<Cost>>,
+}
+
+impl Problem for QAPProblem {
+    fn random_individual(&self, rng: &mut dyn RngCore) -> VecIndividual {
+        let locations: Vec<Gene> = (0..self.size as Gene).collect();
+        let mut left_nodes = VecIndividual::from(&locations);
+        let mut selected_nodes: VecIndividual = VecIndividual::new();
+
+        for _ in &locations {
+            let selected_gene = left_nodes.random_gene(rng);
+            selected_nodes.add_gene(selected_gene);
+            left_nodes.remove_gene(selected_gene)
+        }
+
+        selected_nodes
+    }
+
+    fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
+        Ok(self.cost(individual.genes())? as Fitness)
+    }
+
+    // Swaps are evaluated in O(n) instead of O(n^2)
+    fn eval_move(&self, individual: &VecIndividual, step: &Move) -> Result<Fitness> {
+        match step {
+            Move::Swap(a, b) => {
+                Ok(individual.fitness(self)?
+                    + self.swap_delta(individual.genes(), *a, *b) as Fitness)
+            }
+            _ => {
+                let mut neighbor = individual.clone();
+                neighbor.apply_move(step);
+                self.eval(&neighbor)
+            }
+        }
+    }
+
+    // QAPLIB .sln format: size and cost, followed by the 1-based locations
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        match self.cost(individual.genes()) {
+            Ok(cost) => format!(
+                "{} {}\n{}\n",
+                self.size,
+                cost,
+                individual
+                    .genes()
+                    .iter()
+                    .map(|location| (location + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            Err(err) => format!("Invalid individual: {}", err),
+        }
+    }
+}
+
+impl QAPProblem {
+    pub fn new(name: String, flows: Vec<Vec<Cost>>, distances: Vec<Vec<Cost>>) -> QAPProblem {
+        QAPProblem {
+            name,
+            size: flows.len(),
+            flows,
+            distances,
+        }
+    }
+
+    // QAPLIB .dat files list the size and two square matrices, separated by any whitespace
+    pub fn parse(content: &str) -> Result<QAPProblem> {
+        let mut numbers = content.lines().enumerate().flat_map(|(index, line)| {
+            line.split_whitespace()
+                .map(move |number| parse_number::<Cost>(number, index + 1))
+        });
+
+        let size = numbers.next().ok_or(anyhow!("missing instance size"))??;
+        let size = usize::try_from(size).map_err(|_| anyhow!("invalid instance size {}", size))?;
+
+        let mut matrix = || -> Result<Vec<Vec<Cost>>> {
+            (0..size)
+                .map(|_| {
+                    (0..size)
+                        .map(|_| {
+                            numbers
+                                .next()
+                                .ok_or(anyhow!("expected two {0}x{0} matrices", size))?
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let flows = matrix()?;
+        let distances = matrix()?;
+
+        if numbers.next().is_some() {
+            return Err(anyhow!(
+                "unexpected values after two {0}x{0} matrices",
+                size
+            ));
+        }
+
+        Ok(QAPProblem::new(String::new(), flows, distances))
+    }
+
+    pub fn from_file(path: &str) -> Result<QAPProblem> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let mut problem =
+            QAPProblem::parse(&content).with_context(|| format!("Failed to parse {}", path))?;
+
+        // QAPLIB files do not contain the instance name
+        problem.name = std::path::Path::new(path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(problem)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn cost(&self, locations: &[Gene]) -> Result<Cost> {
+        if locations.len() != self.size || locations.iter().any(|gene| *gene as usize >= self.size)
+        {
+            return Err(anyhow!(
+                "Assignment has to place all {} facilities",
+                self.size
+            ));
+        }
+
+        let mut cost = 0;
+        for (i, location_i) in locations.iter().enumerate() {
+            for (j, location_j) in locations.iter().enumerate() {
+                cost +=
+                    self.flows[i][j] * self.distances[*location_i as usize][*location_j as usize];
+            }
+        }
+        Ok(cost)
+    }
+
+    // Cost change after exchanging the locations of facilities a and b, valid for asymmetric matrices
+    pub fn swap_delta(&self, locations: &[Gene], a: usize, b: usize) -> Cost {
+        if a == b {
+            return 0;
+        }
+
+        let flow = &self.flows;
+        let distance =
+            |i: usize, j: usize| self.distances[locations[i] as usize][locations[j] as usize];
+
+        let mut delta = flow[a][a] * (distance(b, b) - distance(a, a))
+            + flow[a][b] * (distance(b, a) - distance(a, b))
+            + flow[b][a] * (distance(a, b) - distance(b, a))
+            + flow[b][b] * (distance(a, a) - distance(b, b));
+
+        for k in (0..self.size).filter(|k| *k != a && *k != b) {
+            delta += flow[k][a] * (distance(k, b) - distance(k, a))
+                + flow[k][b] * (distance(k, a) - distance(k, b))
+                + flow[a][k] * (distance(b, k) - distance(a, k))
+                + flow[b][k] * (distance(a, k) - distance(b, k));
+        }
+        delta
+    }
+}
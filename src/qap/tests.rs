@@ -0,0 +1,138 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    evolutionary_algorithm::{
+        algorithm::EvolutionaryAlgorithmBuilder,
+        crossover::{CrossoverOperator, PartiallyMappedCrossover},
+        mutation::SwapMutation,
+        selection::TournamentSelector,
+    },
+    individual::{moves::Move, VecIndividual},
+    logger::NoopLogger,
+    neighbor::SwapNeighborhoodOperator,
+    problem::Problem,
+    solver::{Seed, Solver},
+    tabu_search::algorithm::TabuSearchBuilder,
+};
+
+use super::{Cost, QAPProblem};
+
+// Deterministic asymmetric matrices, so that every term of the swap delta matters
+fn generated_instance(size: usize) -> QAPProblem {
+    let matrix = |seed: Cost| -> Vec<Vec<Cost>> {
+        (0..size as Cost)
+            .map(|i| {
+                (0..size as Cost)
+                    .map(|j| (i * 31 + j * 17 + seed * i * j) % 10)
+                    .collect()
+            })
+            .collect()
+    };
+    QAPProblem::new(String::from("generated"), matrix(7), matrix(3))
+}
+
+#[test]
+fn parse_qaplib_instance() {
+    let content = "3\n\n0 1 2\n1 0 3\n2 3 0\n\n0 5 2\n5 0 1\n 2 1\n0\n";
+    let problem = QAPProblem::parse(content).unwrap();
+
+    assert_eq!(problem.size(), 3);
+    // Flows 1-2 and 2-3 are placed at distance 2 and 1, flow 1-3 at distance 5
+    assert_eq!(problem.cost(&[0, 2, 1]).unwrap(), 2 * (2 + 3 + 10));
+    assert!(problem.cost(&[0, 1]).is_err());
+    assert!(QAPProblem::parse("2\n0 1\n1 0\n0 1\n").is_err());
+    assert!(problem
+        .serialize_indiviual(&VecIndividual::from(vec![0, 2, 1]))
+        .starts_with("3 30\n1 3 2"));
+}
+
+#[test]
+fn swap_delta_matches_full_evaluation() {
+    let problem = generated_instance(12);
+    let individual = problem.random_individual(&mut StdRng::seed_from_u64(0));
+    let cost = problem.cost(individual.genes()).unwrap();
+
+    for a in 0..12 {
+        for b in 0..12 {
+            let mut neighbor = individual.clone();
+            neighbor.genes_mut().swap(a, b);
+
+            assert_eq!(
+                cost + problem.swap_delta(individual.genes(), a, b),
+                problem.cost(neighbor.genes()).unwrap()
+            );
+            assert_eq!(
+                problem.eval_move(&individual, &Move::Swap(a, b)).unwrap(),
+                problem.eval(&neighbor).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn tabu_search_with_swaps_on_large_instance() {
+    let problem = generated_instance(120);
+    let mut tabu_search = TabuSearchBuilder::default()
+        .iterations(50)
+        .tabu_list_size(10)
+        .neighborhood_operator(Box::new(SwapNeighborhoodOperator::new(100)))
+        .logger(Box::new(NoopLogger))
+        .build()
+        .unwrap();
+
+    let (fitness, solution) = tabu_search.solve(&problem).unwrap();
+
+    assert_eq!(fitness, problem.eval(&solution).unwrap());
+}
+
+#[test]
+fn identical_seeds_give_identical_results() {
+    let problem = generated_instance(20);
+    let tabu_search = |seed: Seed| {
+        TabuSearchBuilder::default()
+            .iterations(20)
+            .tabu_list_size(5)
+            .neighborhood_operator(Box::new(SwapNeighborhoodOperator::new(10)))
+            .logger(Box::new(NoopLogger))
+            .seed(seed)
+            .build()
+            .unwrap()
+    };
+    let evolutionary_algorithm = |seed: Seed| {
+        EvolutionaryAlgorithmBuilder::new()
+            .population_size(10)
+            .generations(5)
+            .crossover_prob(0.7)
+            .mutation_prob(0.3)
+            .crossover_operator(CrossoverOperator::TwoChildrenCrossoverOperator(Box::new(
+                PartiallyMappedCrossover {},
+            )))
+            .mutation_operator(Box::new(SwapMutation {}))
+            .selection_operator(Box::new(TournamentSelector::new(3)))
+            .logger(Box::new(NoopLogger))
+            .seed(seed)
+            .build()
+            .unwrap()
+    };
+
+    let solvers: [[Box<dyn Solver>; 3]; 2] = [
+        [
+            Box::new(tabu_search(7)),
+            Box::new(tabu_search(7)),
+            Box::new(tabu_search(8)),
+        ],
+        [
+            Box::new(evolutionary_algorithm(7)),
+            Box::new(evolutionary_algorithm(7)),
+            Box::new(evolutionary_algorithm(8)),
+        ],
+    ];
+    for [mut first, mut same_seed, mut other_seed] in solvers {
+        // Repeated runs continue with seeds drawn from the previous run
+        let first_run = first.solve(&problem).unwrap();
+        let second_run = first.solve(&problem).unwrap();
+        assert_eq!(first_run, same_seed.solve(&problem).unwrap());
+        assert_eq!(second_run, same_seed.solve(&problem).unwrap());
+        assert_ne!(first_run.1, other_seed.solve(&problem).unwrap().1);
+    }
+}
@@ -1,5 +1,3 @@
-use std::fs::read_to_string;
-
 use crate::{
     evolutionary_algorithm::{
         algorithm::{EvolutionaryAlgorithm, EvolutionaryAlgorithmBuilder},
@@ -9,55 +7,62 @@ use crate::{
     },
     greedy_algorithm::GreedyAlgorithm,
     individual::Fitness,
-    logger::{inverse_fitness, CSVEntry, CSVLogger, PersistableLogger},
+    logger::{CSVEntry, CSVLogger, PersistableLogger},
     neighbor::SwapNeighborhoodOperator,
-    problem::Problem,
-    problem_loader::CVRProblem,
+    problem::{ObjectiveSense, Problem},
+    problem_loader::{best_known::BestKnownSolution, distance::DistancePrecision, CVRProblem},
     simulated_annealing::{
         algorithm::{SimulatedAnnealing, SimulatedAnnealingBuilder},
         cooling_schedule::ExponentialCoolingScheduleBuilder,
         criterion_operator::BoltzmanProbabilityCriterionOperator,
     },
-    solver::Solver,
+    solver::{random_seed, Seed, Solver},
     tabu_search::algorithm::{TabuSearch, TabuSearchBuilder},
     tssa::algorithm::{TSSABuilder, TSSA},
 };
+use executor::BenchmarkExecutor;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+pub mod executor;
 
 pub struct Score {
-    best: f32,
-    worst: f32,
-    avg: f32,
-    std: f32,
+    best: Fitness,
+    worst: Fitness,
+    avg: Fitness,
+    std: Fitness,
+    gap: Option<Fitness>,
 }
 
 impl Score {
-    pub fn new(scores: Vec<Fitness>) -> Self {
-        let inversed_scores: Vec<Fitness> =
-            scores.iter().map(|score| inverse_fitness(*score)).collect();
-        Score {
-            best: *inversed_scores
+    // None without any scores, e.g. when every run of an algorithm failed
+    pub fn new(
+        scores: Vec<Fitness>,
+        sense: ObjectiveSense,
+        best_known: Option<&BestKnownSolution>,
+    ) -> Option<Self> {
+        let best = *scores.iter().max_by(|x, y| sense.compare(**x, **y))?;
+        Some(Score {
+            best,
+            gap: best_known.and_then(|best_known| best_known.gap(best)),
+            worst: *scores
                 .iter()
-                .min_by(|x, y| f32::total_cmp(x, y))
+                .min_by(|x, y| sense.compare(**x, **y))
                 .unwrap(),
-            worst: *inversed_scores
-                .iter()
-                .max_by(|x, y| f32::total_cmp(x, y))
-                .unwrap(),
-            avg: inversed_scores.iter().sum::<Fitness>() / scores.len() as Fitness,
-            std: calculate_std_dev(&inversed_scores),
-        }
+            avg: scores.iter().sum::<Fitness>() / scores.len() as Fitness,
+            std: calculate_std_dev(&scores),
+        })
     }
 }
 
 pub struct ScoreSet {
     instance: String,
-    ea: Score,
-    tabu: Score,
-    random: Score,
-    greedy: Score,
-    sa: Score,
-    tssa: Score,
-    saea: Score,
+    ea: Option<Score>,
+    tabu: Option<Score>,
+    random: Option<Score>,
+    greedy: Option<Score>,
+    sa: Option<Score>,
+    tssa: Option<Score>,
+    saea: Option<Score>,
 }
 
 impl ScoreSet {
@@ -68,121 +73,126 @@ impl ScoreSet {
             String::from("Random: worst"),
             String::from("Random: avg"),
             String::from("Random: std"),
+            String::from("Random: gap"),
             String::from("Greedy: best"),
             String::from("Greedy: worst"),
             String::from("Greedy: avg"),
             String::from("Greedy: std"),
+            String::from("Greedy: gap"),
             String::from("Evolutionary: best"),
             String::from("Evolutionary: worst"),
             String::from("Evolutionary: avg"),
             String::from("Evolutionary: std"),
+            String::from("Evolutionary: gap"),
             String::from("Tabu: best"),
             String::from("Tabu: worst"),
             String::from("Tabu: avg"),
             String::from("Tabu: std"),
+            String::from("Tabu: gap"),
             String::from("SA: best"),
             String::from("SA: worst"),
             String::from("SA: avg"),
             String::from("SA: std"),
+            String::from("SA: gap"),
             String::from("TSSA: best"),
             String::from("TSSA: worst"),
             String::from("TSSA: avg"),
             String::from("TSSA: std"),
+            String::from("TSSA: gap"),
             String::from("SAEA: best"),
             String::from("SAEA: worst"),
             String::from("SAEA: avg"),
             String::from("SAEA: std"),
+            String::from("SAEA: gap"),
         ]
     }
 }
 
+impl Score {
+    // Best, worst, average, standard deviation and gap, left empty when every run failed
+    fn columns(score: Option<&Score>) -> Vec<String> {
+        match score {
+            Some(score) => vec![
+                score.best.to_string(),
+                score.worst.to_string(),
+                score.avg.to_string(),
+                score.std.to_string(),
+                score.gap.map(|gap| gap.to_string()).unwrap_or_default(),
+            ],
+            None => vec![String::new(); 5],
+        }
+    }
+}
+
 impl From<&ScoreSet> for CSVEntry {
     fn from(score_set: &ScoreSet) -> CSVEntry {
-        CSVEntry::from(vec![
-            score_set.instance.to_string(),
-            score_set.random.best.to_string(),
-            score_set.random.worst.to_string(),
-            score_set.random.avg.to_string(),
-            score_set.random.std.to_string(),
-            score_set.greedy.best.to_string(),
-            score_set.greedy.worst.to_string(),
-            score_set.greedy.avg.to_string(),
-            score_set.greedy.std.to_string(),
-            score_set.ea.best.to_string(),
-            score_set.ea.worst.to_string(),
-            score_set.ea.avg.to_string(),
-            score_set.ea.std.to_string(),
-            score_set.tabu.best.to_string(),
-            score_set.tabu.worst.to_string(),
-            score_set.tabu.avg.to_string(),
-            score_set.tabu.std.to_string(),
-            score_set.sa.best.to_string(),
-            score_set.sa.worst.to_string(),
-            score_set.sa.avg.to_string(),
-            score_set.sa.std.to_string(),
-            score_set.tssa.best.to_string(),
-            score_set.tssa.worst.to_string(),
-            score_set.tssa.avg.to_string(),
-            score_set.tssa.std.to_string(),
-            score_set.saea.best.to_string(),
-            score_set.saea.worst.to_string(),
-            score_set.saea.avg.to_string(),
-            score_set.saea.std.to_string(),
-        ])
+        let mut columns = vec![score_set.instance.to_string()];
+        for score in [
+            &score_set.random,
+            &score_set.greedy,
+            &score_set.ea,
+            &score_set.tabu,
+            &score_set.sa,
+            &score_set.tssa,
+            &score_set.saea,
+        ] {
+            columns.extend(Score::columns(score.as_ref()));
+        }
+        CSVEntry::from(columns)
     }
 }
 
-fn optimal_ea() -> EvolutionaryAlgorithm {
+fn optimal_ea(log_file: &str, seed: Seed) -> EvolutionaryAlgorithm {
     EvolutionaryAlgorithmBuilder::new()
         .population_size(500)
         .generations(500)
         .crossover_prob(0.7)
         .mutation_prob(0.6)
-        .logger(Box::new(CSVLogger::new("comparisons", None)))
+        .logger(Box::new(CSVLogger::new(log_file, None)))
         .crossover_operator(CrossoverOperator::SingleChildCrossoverOperator(Box::new(
             OrderedCrossover {},
         )))
         .mutation_operator(Box::new(SwapMutation {}))
         .selection_operator(Box::new(TournamentSelector::new(10)))
+        .seed(seed)
         .build()
         .unwrap()
 }
 
-fn optimal_tabu() -> TabuSearch {
+fn optimal_tabu(log_file: &str, seed: Seed) -> TabuSearch {
     TabuSearchBuilder::default()
         .iterations(1000)
         .tabu_list_size(20)
         .neighborhood_operator(Box::new(SwapNeighborhoodOperator::new(40)))
-        .logger(Box::new(CSVLogger::new(
-            format!("{}-tabu", "comparisons").as_str(),
-            None,
-        )))
+        .logger(Box::new(CSVLogger::new(log_file, None)))
+        .seed(seed)
         .build()
         .unwrap()
 }
 
-fn optimal_sa() -> SimulatedAnnealing {
+fn optimal_sa(log_file: &str, seed: Seed) -> SimulatedAnnealing {
     SimulatedAnnealingBuilder::default()
         .iterations(10000)
         .cooling_schedule(Box::new(
             ExponentialCoolingScheduleBuilder::default()
-                .initial_temperature(1f32)
+                .initial_temperature(100f32)
                 .cooling_factor(0.999f32)
                 .build()
                 .unwrap(),
         ))
         .neighbor_operator(Box::new(SwapNeighborhoodOperator::new(40)))
         .criterion_operator(Box::new(BoltzmanProbabilityCriterionOperator {}))
-        .logger(Box::new(CSVLogger::new("sa-comparisions", None)))
+        .logger(Box::new(CSVLogger::new(log_file, None)))
+        .seed(seed)
         .build()
         .unwrap()
 }
-fn optimal_tssa() -> TSSA {
+fn optimal_tssa(log_file: &str, seed: Seed) -> TSSA {
     TSSABuilder::default()
         .iterations(20)
         .cooling_schedule(Box::new(
             ExponentialCoolingScheduleBuilder::default()
-                .initial_temperature(1f32)
+                .initial_temperature(100f32)
                 .cooling_factor(0.999f32)
                 .build()
                 .unwrap(),
@@ -191,80 +201,33 @@ fn optimal_tssa() -> TSSA {
         .neighborhood_operator(Box::new(SwapNeighborhoodOperator::new(40)))
         .tabu_list_size(200)
         .criterion_operator(Box::new(BoltzmanProbabilityCriterionOperator {}))
-        .logger(Box::new(CSVLogger::new("sa-comparisions", None)))
+        .logger(Box::new(CSVLogger::new(log_file, None)))
+        .seed(seed)
         .build()
         .unwrap()
 }
 
-fn optimal_saea() -> EvolutionaryAlgorithm {
+fn optimal_saea(log_file: &str, seed: Seed) -> EvolutionaryAlgorithm {
     EvolutionaryAlgorithmBuilder::new()
         .population_size(200)
         .generations(1000)
         .crossover_prob(0.6)
         .mutation_prob(0.3)
-        .logger(Box::new(CSVLogger::new("sa-comparisions", None)))
+        .logger(Box::new(CSVLogger::new(log_file, None)))
         .crossover_operator(CrossoverOperator::SingleChildCrossoverOperator(Box::new(
             OrderedCrossover {},
         )))
         .mutation_operator(Box::new(SwapMutation {}))
         .selection_operator(Box::new(SimulatedAnnealingRouletteSelector::new(1.0, 0.99)))
+        .seed(seed)
         .build()
         .unwrap()
 }
 
-fn test_ea(problem: &dyn Problem, repeats: u16) -> Vec<Fitness> {
-    let mut ea = optimal_ea();
-    (0..repeats)
-        .map(|_| {
-            let (score, _) = ea.solve(problem).unwrap();
-            score
-        })
-        .collect::<Vec<Fitness>>()
-}
-
-fn test_taboo(problem: &dyn Problem, repeats: u16) -> Vec<Fitness> {
-    let mut ea = optimal_tabu();
-    (0..repeats)
-        .map(|_| {
-            let (score, _) = ea.solve(problem).unwrap();
-            score
-        })
-        .collect::<Vec<Fitness>>()
-}
-fn test_sa(problem: &dyn Problem, repeats: u16) -> Vec<Fitness> {
-    let mut ea = optimal_sa();
-    (0..repeats)
-        .map(|_| {
-            let (score, _) = ea.solve(problem).unwrap();
-            score
-        })
-        .collect::<Vec<Fitness>>()
-}
-
-fn test_tssa(problem: &dyn Problem, repeats: u16) -> Vec<Fitness> {
-    let mut ea = optimal_tssa();
-    (0..repeats)
-        .map(|_| {
-            let (score, _) = ea.solve(problem).unwrap();
-            score
-        })
-        .collect::<Vec<Fitness>>()
-}
-
-fn test_saea(problem: &dyn Problem, repeats: u16) -> Vec<Fitness> {
-    let mut ea = optimal_saea();
-    (0..repeats)
-        .map(|_| {
-            let (score, _) = ea.solve(problem).unwrap();
-            score
-        })
-        .collect::<Vec<Fitness>>()
-}
-
-fn test_greedy(problem: &CVRProblem) -> Vec<Fitness> {
+fn test_greedy(problem: &CVRProblem, rng: &mut dyn RngCore) -> Vec<Fitness> {
     let mut greedy = GreedyAlgorithm::new(problem);
     problem
-        .random_individual()
+        .random_individual(rng)
         .genes()
         .iter()
         .map(|gene| {
@@ -274,10 +237,10 @@ fn test_greedy(problem: &CVRProblem) -> Vec<Fitness> {
         .collect::<Vec<Fitness>>()
 }
 
-fn test_random(problem: &CVRProblem, repeats: u16) -> Vec<Fitness> {
+fn test_random(problem: &CVRProblem, repeats: u16, rng: &mut dyn RngCore) -> Vec<Fitness> {
     (0..repeats)
         .map(|_| {
-            let solution = problem.random_individual();
+            let solution = problem.random_individual(rng);
             problem.eval(&solution).unwrap()
         })
         .collect::<Vec<Fitness>>()
@@ -285,18 +248,67 @@ fn test_random(problem: &CVRProblem, repeats: u16) -> Vec<Fitness> {
 
 fn calculate_std_dev(data: &[Fitness]) -> Fitness {
     let mean: Fitness = data.iter().sum::<Fitness>() / data.len() as Fitness;
-    let variance: f32 = data
+    let variance: Fitness = data
         .iter()
         .map(|value| {
             let diff = value - mean;
             diff * diff
         })
-        .sum::<f32>()
-        / data.len() as f32;
+        .sum::<Fitness>()
+        / data.len() as Fitness;
 
     variance.sqrt()
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    Evolutionary,
+    Tabu,
+    SimulatedAnnealing,
+    TabuSimulatedAnnealing,
+    SimulatedAnnealingEvolutionary,
+}
+
+impl Algorithm {
+    const ALL: [Algorithm; 5] = [
+        Algorithm::Evolutionary,
+        Algorithm::Tabu,
+        Algorithm::SimulatedAnnealing,
+        Algorithm::TabuSimulatedAnnealing,
+        Algorithm::SimulatedAnnealingEvolutionary,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Evolutionary => "ea",
+            Algorithm::Tabu => "tabu",
+            Algorithm::SimulatedAnnealing => "sa",
+            Algorithm::TabuSimulatedAnnealing => "tssa",
+            Algorithm::SimulatedAnnealingEvolutionary => "saea",
+        }
+    }
+
+    fn solver(&self, log_file: &str, seed: Seed) -> Box<dyn Solver> {
+        match self {
+            Algorithm::Evolutionary => Box::new(optimal_ea(log_file, seed)),
+            Algorithm::Tabu => Box::new(optimal_tabu(log_file, seed)),
+            Algorithm::SimulatedAnnealing => Box::new(optimal_sa(log_file, seed)),
+            Algorithm::TabuSimulatedAnnealing => Box::new(optimal_tssa(log_file, seed)),
+            Algorithm::SimulatedAnnealingEvolutionary => Box::new(optimal_saea(log_file, seed)),
+        }
+    }
+}
+
+// Single run of an algorithm, a fresh solver is built for every run so that its seed alone
+// reproduces it
+struct Job {
+    instance: usize,
+    algorithm: Algorithm,
+    seed: Seed,
+}
+
+const REPEATS: u16 = 10;
+
 pub fn run_comparisons() {
     let instances: Vec<&str> = vec![
         "./csv/A-n32-k5",
@@ -308,44 +320,89 @@ pub fn run_comparisons() {
         "./csv/A-n60-k9",
     ];
 
-    let mut logger: CSVLogger<ScoreSet> =
-        CSVLogger::new("./csv/comparisons-sa.csv", Some(ScoreSet::headers()));
-
-    for instance in instances {
-        let problem_contents = read_to_string(instance).unwrap();
-        let mut problem = CVRProblem::from(problem_contents);
-        problem.precalculate_distances();
-
-        let ea_scores = test_ea(&problem, 10);
-        let ea_summary = Score::new(ea_scores);
+    let problems: Vec<(&str, CVRProblem)> = instances
+        .into_iter()
+        .filter_map(|instance| match CVRProblem::from_file(instance) {
+            Ok(mut problem) => {
+                // Best known solutions are published for TSPLIB rounded distances
+                problem.set_distance_precision(DistancePrecision::Rounded);
+                Some((instance, problem))
+            }
+            Err(err) => {
+                println!("Skipping instance {}: {:#}", instance, err);
+                None
+            }
+        })
+        .collect();
 
-        let greedy_scores = test_greedy(&problem);
-        let greedy_summary = Score::new(greedy_scores);
+    // Every job seed is drawn from this one, which is enough to repeat the whole comparison
+    let seed = random_seed();
+    println!("Running comparisons with seed {}", seed);
+    let mut rng = StdRng::seed_from_u64(seed);
 
-        let random_scores = test_random(&problem, 10000);
-        let random_summary = Score::new(random_scores);
+    let mut jobs = Vec::new();
+    for instance in 0..problems.len() {
+        for algorithm in Algorithm::ALL {
+            for _ in 0..REPEATS {
+                jobs.push(Job {
+                    instance,
+                    algorithm,
+                    seed: rng.gen(),
+                });
+            }
+        }
+    }
 
-        let tabu_scores = test_taboo(&problem, 10);
-        let tabu_summary = Score::new(tabu_scores);
+    // Every run logs its progress to a file of its own, named after the instance, algorithm and seed
+    let results = BenchmarkExecutor::with_available_parallelism().run(&jobs, |job| {
+        let (instance, problem) = &problems[job.instance];
+        let log_file = format!("{}-{}-{}.csv", instance, job.algorithm.name(), job.seed);
+        let (score, _) = job.algorithm.solver(&log_file, job.seed).solve(problem)?;
+        Ok(score)
+    });
 
-        let sa_scores = test_sa(&problem, 10);
-        let sa_summary = Score::new(sa_scores);
+    // Failed runs are reported and left out of the scores
+    let scores: Vec<Option<Fitness>> = jobs
+        .iter()
+        .zip(results)
+        .map(|(job, result)| match result {
+            Ok(score) => Some(score),
+            Err(err) => {
+                println!(
+                    "Skipping {:?} on {} with seed {}: {:#}",
+                    job.algorithm, problems[job.instance].0, job.seed, err
+                );
+                None
+            }
+        })
+        .collect();
+    let algorithm_scores = |instance: usize, algorithm: Algorithm| {
+        jobs.iter()
+            .zip(&scores)
+            .filter(|(job, _)| job.instance == instance && job.algorithm == algorithm)
+            .filter_map(|(_, score)| *score)
+            .collect::<Vec<Fitness>>()
+    };
 
-        let tssa_scores = test_tssa(&problem, 10);
-        let tssa_summary = Score::new(tssa_scores);
+    let mut logger: CSVLogger<ScoreSet> =
+        CSVLogger::new("./csv/comparisons-sa.csv", Some(ScoreSet::headers()));
 
-        let saea_scores = test_saea(&problem, 10);
-        let saea_summary = Score::new(saea_scores);
+    for (index, (instance, problem)) in problems.iter().enumerate() {
+        let summary =
+            |scores: Vec<Fitness>| Score::new(scores, problem.sense(), problem.best_known());
 
         logger.log(ScoreSet {
-            instance: String::from(instance),
-            ea: ea_summary,
-            random: random_summary,
-            greedy: greedy_summary,
-            tabu: tabu_summary,
-            sa: sa_summary,
-            tssa: tssa_summary,
-            saea: saea_summary,
+            instance: String::from(*instance),
+            ea: summary(algorithm_scores(index, Algorithm::Evolutionary)),
+            random: summary(test_random(problem, 10000, &mut rng)),
+            greedy: summary(test_greedy(problem, &mut rng)),
+            tabu: summary(algorithm_scores(index, Algorithm::Tabu)),
+            sa: summary(algorithm_scores(index, Algorithm::SimulatedAnnealing)),
+            tssa: summary(algorithm_scores(index, Algorithm::TabuSimulatedAnnealing)),
+            saea: summary(algorithm_scores(
+                index,
+                Algorithm::SimulatedAnnealingEvolutionary,
+            )),
         })
     }
 
@@ -0,0 +1,68 @@
+use std::num::NonZeroUsize;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+
+#[cfg(test)]
+mod tests;
+
+// Runs independent jobs, e.g. the repeats of every algorithm on every instance, on worker threads.
+// Workers take the next job as soon as they finish one, so long runs do not hold back the rest.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkExecutor {
+    threads: NonZeroUsize,
+}
+
+impl BenchmarkExecutor {
+    pub fn new(threads: NonZeroUsize) -> Self {
+        BenchmarkExecutor { threads }
+    }
+
+    // One worker for every available core
+    pub fn with_available_parallelism() -> Self {
+        BenchmarkExecutor::new(thread::available_parallelism().unwrap_or(NonZeroUsize::MIN))
+    }
+
+    // Results are in the order of the jobs, whichever worker ran them. A job that fails or
+    // panics only gives an error in its place, the other jobs still run.
+    pub fn run<J, R, F>(&self, jobs: &[J], run: F) -> Vec<Result<R>>
+    where
+        J: Sync,
+        R: Send,
+        F: Fn(&J) -> Result<R> + Sync,
+    {
+        let next_job = AtomicUsize::new(0);
+        let workers = self.threads.get().min(jobs.len());
+        let (run, next_job) = (&run, &next_job);
+
+        let mut results: Vec<(usize, Result<R>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(move || {
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next_job.fetch_add(1, Ordering::Relaxed);
+                            let Some(job) = jobs.get(index) else {
+                                return results;
+                            };
+                            let result = catch_unwind(AssertUnwindSafe(|| run(job)))
+                                .unwrap_or_else(|_| Err(anyhow!("Job {} panicked", index)));
+                            results.push((index, result));
+                        }
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                // Panics are caught per job, so the workers themselves always finish
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        });
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
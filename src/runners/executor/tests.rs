@@ -0,0 +1,48 @@
+use std::num::NonZeroUsize;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::anyhow;
+
+use super::BenchmarkExecutor;
+
+#[test]
+fn results_follow_the_order_of_the_jobs() {
+    let jobs: Vec<u64> = (0..50).collect();
+
+    for threads in [1, 4, 100] {
+        let executor = BenchmarkExecutor::new(NonZeroUsize::new(threads).unwrap());
+        // Earlier jobs take longer, so they finish after the later ones
+        let results = executor.run(&jobs, |job| {
+            thread::sleep(Duration::from_micros(50 - job));
+            Ok(job * job)
+        });
+
+        assert_eq!(
+            results
+                .into_iter()
+                .map(|result| result.unwrap())
+                .collect::<Vec<u64>>(),
+            jobs.iter().map(|job| job * job).collect::<Vec<u64>>()
+        );
+    }
+}
+
+#[test]
+fn failed_jobs_do_not_stop_the_others() {
+    let jobs: Vec<u64> = (0..10).collect();
+    let executor = BenchmarkExecutor::new(NonZeroUsize::new(3).unwrap());
+
+    let results = executor.run(&jobs, |job| match job {
+        3 => Err(anyhow!("Job failed")),
+        7 => panic!("Job panicked"),
+        job => Ok(job * job),
+    });
+
+    for (job, result) in jobs.iter().zip(results) {
+        match job {
+            3 | 7 => assert!(result.is_err()),
+            job => assert_eq!(result.unwrap(), job * job),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
         algorithm::{EvolutionaryAlgorithm, EvolutionaryAlgorithmBuilder},
         crossover::{CrossoverOperator, OrderedCrossover},
         mutation::SwapMutation,
-        selection::{RouletteSelector, SimulatedAnnealingRouletteSelector, TournamentSelector},
+        selection::{RouletteSelector, SimulatedAnnealingRouletteSelector},
     },
     logger::CSVLogger,
 };
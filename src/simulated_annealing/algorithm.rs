@@ -1,49 +1,60 @@
 use crate::{
-    individual::{Fitness, VecIndividual},
-    logger::{inverse_fitness, CSVEntry, PersistableLogger},
-    neighbor::NeighborOperator,
-    population::Population,
+    evaluation::Evaluation,
+    individual::{Fitness, Individual, VecIndividual},
+    logger::{CSVEntry, PersistableLogger},
+    neighbor::{scored_moves, NeighborOperator},
     problem::Problem,
-    solver::Solver,
+    solver::{random_seed, Seed, Solver},
+    termination::{EvaluationCounter, Progress, TerminationCriterion},
 };
 use anyhow::{Context, Result};
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 
 use super::{cooling_schedule::CoolingSchedule, criterion_operator::CriterionOperator};
 
 pub type Temperature = f32;
 
-pub struct SimulatedAnnealing {
-    iterations: u32,
+pub struct SimulatedAnnealing<I = VecIndividual> {
+    termination: TerminationCriterion,
     criterion_operator: Box<dyn CriterionOperator>,
     cooling_schedule: Box<dyn CoolingSchedule>,
-    neighbor_operator: Box<dyn NeighborOperator>,
+    neighbor_operator: Box<dyn NeighborOperator<I>>,
     logger: Box<dyn PersistableLogger<SimulatedAnnealingIterationInfo>>,
+    evaluation: Evaluation,
+    seed: Seed,
 }
 
-pub struct Solution {
-    pub individual: VecIndividual,
+// Current individual of the annealing and the best one it has visited
+pub struct Solution<I = VecIndividual> {
+    pub individual: I,
     pub fitness: Fitness,
+    pub best_individual: I,
     pub best_fitness: Fitness,
 }
 
-impl Solver for SimulatedAnnealing {
-    fn solve(&mut self, problem: &dyn Problem) -> Result<(Fitness, VecIndividual)> {
-        let mut solution = self.get_random_solution(problem)?;
+impl<I: Individual> Solver<I> for SimulatedAnnealing<I> {
+    fn solve(&mut self, problem: &dyn Problem<I>) -> Result<(Fitness, I)> {
+        let problem = &EvaluationCounter::new(problem);
+        let mut progress = Progress::new(problem.sense());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut solution = self.get_random_solution(problem, &mut rng)?;
 
-        for i in 0..self.iterations {
-            self.log(problem, &solution, i);
-            solution = self.solution_iteration(solution, problem)?;
+        while !self.termination.is_met(&progress) {
+            self.log(problem, &solution, progress.iterations() as u32);
+            solution = self.solution_iteration(solution, problem, &mut rng)?;
             self.decrease_temperature();
+            progress.record(solution.best_fitness, problem.evaluations());
         }
 
+        self.seed = rng.gen();
         self.logger.flush()?;
 
-        Ok((solution.fitness, solution.individual))
+        Ok((solution.best_fitness, solution.best_individual))
     }
 }
 
-impl SimulatedAnnealing {
-    fn log(&mut self, problem: &dyn Problem, solution: &Solution, iteration: u32) {
+impl<I: Individual> SimulatedAnnealing<I> {
+    fn log(&mut self, problem: &dyn Problem<I>, solution: &Solution<I>, iteration: u32) {
         self.logger.log(SimulatedAnnealingIterationInfo::new(
             self.configuration_name(),
             iteration,
@@ -56,66 +67,79 @@ impl SimulatedAnnealing {
 
     fn configuration_name(&self) -> String {
         format!(
-            "neighbor_operator: {}, cooling_schedule {}",
+            "{}, neighbor_operator: {}, cooling_schedule {}, seed: {}",
+            self.termination.name(),
             self.neighbor_operator.name(),
             self.cooling_schedule.name(),
+            self.seed,
         )
     }
     fn solution_iteration(
         &mut self,
-        solution: Solution,
-        problem: &dyn Problem,
-    ) -> Result<Solution> {
-        let new_solution = self.get_neighbor(&solution, problem)?;
-
-        if new_solution.fitness > solution.fitness {
-            return Ok(Solution {
-                fitness: new_solution.fitness,
-                best_fitness: solution.best_fitness.max(new_solution.fitness),
-                individual: new_solution.individual,
-            });
-        }
+        solution: Solution<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Solution<I>> {
+        let (individual, fitness) = self.get_neighbor(&solution, problem, rng)?;
+        let sense = problem.sense();
+        let accepted = sense.is_better(fitness, solution.fitness)
+            || self
+                .secondary_acceptance_criteria(sense.improvement(fitness, solution.fitness), rng);
 
-        if self.secondary_acceptance_criteria(solution.fitness - new_solution.fitness) {
-            return Ok(Solution {
-                fitness: new_solution.fitness,
-                best_fitness: solution.best_fitness.max(new_solution.fitness),
-                individual: new_solution.individual,
-            });
+        if !accepted {
+            return Ok(solution);
         }
 
-        Ok(solution)
+        let (best_individual, best_fitness) = match sense.is_better(fitness, solution.best_fitness)
+        {
+            true => (individual.clone(), fitness),
+            false => (solution.best_individual, solution.best_fitness),
+        };
+        Ok(Solution {
+            individual,
+            fitness,
+            best_individual,
+            best_fitness,
+        })
     }
 
-    fn get_neighbor(&self, solution: &Solution, problem: &dyn Problem) -> Result<Solution> {
-        let neighbors = self
-            .neighbor_operator
-            .get_neighborhood(&solution.individual);
-
-        let population = Population::new(neighbors);
+    fn get_neighbor(
+        &self,
+        solution: &Solution<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(I, Fitness)> {
+        let steps = self.neighbor_operator.get_moves(&solution.individual, rng);
+        let (step, _) = scored_moves(problem, &solution.individual, steps, &self.evaluation)?
+            .into_iter()
+            .next()
+            .context("Neighborhood is empty")?;
 
-        let (individual, fitness) = population.highest_fitness(problem);
+        let mut individual = solution.individual.clone();
+        individual.apply_move(&step);
+        let fitness = individual.fitness(problem)?;
 
-        Ok(Solution {
-            individual: individual.clone(),
-            fitness,
-            best_fitness: fitness,
-        })
+        Ok((individual, fitness))
     }
 
-    fn secondary_acceptance_criteria(&self, fitness_diff: Fitness) -> bool {
+    fn secondary_acceptance_criteria(&self, fitness_diff: Fitness, rng: &mut dyn RngCore) -> bool {
         self.criterion_operator
-            .criterion(fitness_diff, self.cooling_schedule.temperature())
+            .criterion(fitness_diff, self.cooling_schedule.temperature(), rng)
     }
 
     fn decrease_temperature(&mut self) {
         self.cooling_schedule.cooldown()
     }
 
-    fn get_random_solution(&self, problem: &dyn Problem) -> Result<Solution> {
-        let individual = problem.random_individual();
-        let fitness = problem.eval(&individual)?;
+    fn get_random_solution(
+        &self,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Solution<I>> {
+        let individual = problem.random_individual(rng);
+        let fitness = individual.fitness(problem)?;
         Ok(Solution {
+            best_individual: individual.clone(),
             individual,
             fitness,
             best_fitness: fitness,
@@ -123,16 +147,33 @@ impl SimulatedAnnealing {
     }
 }
 
-#[derive(Default)]
-pub struct SimulatedAnnealingBuilder {
+pub struct SimulatedAnnealingBuilder<I = VecIndividual> {
     iterations: Option<u32>,
-    neighbor_operator: Option<Box<dyn NeighborOperator>>,
+    termination: Option<TerminationCriterion>,
+    neighbor_operator: Option<Box<dyn NeighborOperator<I>>>,
     cooling_schedule: Option<Box<dyn CoolingSchedule>>,
     criterion_operator: Option<Box<dyn CriterionOperator>>,
     logger: Option<Box<dyn PersistableLogger<SimulatedAnnealingIterationInfo>>>,
+    evaluation: Option<Evaluation>,
+    seed: Option<Seed>,
 }
 
-impl SimulatedAnnealingBuilder {
+impl<I> Default for SimulatedAnnealingBuilder<I> {
+    fn default() -> Self {
+        SimulatedAnnealingBuilder {
+            iterations: None,
+            termination: None,
+            neighbor_operator: None,
+            cooling_schedule: None,
+            criterion_operator: None,
+            logger: None,
+            evaluation: None,
+            seed: None,
+        }
+    }
+}
+
+impl<I> SimulatedAnnealingBuilder<I> {
     pub fn new() -> Self {
         SimulatedAnnealingBuilder::default()
     }
@@ -152,7 +193,7 @@ impl SimulatedAnnealingBuilder {
         self
     }
 
-    pub fn neighbor_operator(mut self, neighbor_operator: Box<dyn NeighborOperator>) -> Self {
+    pub fn neighbor_operator(mut self, neighbor_operator: Box<dyn NeighborOperator<I>>) -> Self {
         self.neighbor_operator = Some(neighbor_operator);
         self
     }
@@ -165,15 +206,36 @@ impl SimulatedAnnealingBuilder {
         self
     }
 
-    pub fn build(self) -> Result<SimulatedAnnealing> {
+    pub fn termination(mut self, termination: TerminationCriterion) -> Self {
+        self.termination = Some(termination);
+        self
+    }
+
+    pub fn evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = Some(evaluation);
+        self
+    }
+
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Result<SimulatedAnnealing<I>> {
         Ok(SimulatedAnnealing {
-            iterations: self.iterations.context("No iterations")?,
+            termination: TerminationCriterion::with_iterations(
+                self.termination,
+                self.iterations.map(u64::from),
+            )
+            .context("No iterations")?,
             cooling_schedule: self.cooling_schedule.context("No cooling schedule")?,
             criterion_operator: self.criterion_operator.context("No criterion operator")?,
             logger: self.logger.context("Missing logger")?,
             neighbor_operator: self
                 .neighbor_operator
                 .context("Missing neighbor operator")?,
+            evaluation: self.evaluation.unwrap_or_default(),
+            seed: self.seed.unwrap_or_else(random_seed),
         })
     }
 }
@@ -188,16 +250,16 @@ pub struct SimulatedAnnealingIterationInfo {
 }
 
 impl SimulatedAnnealingIterationInfo {
-    pub fn new(
+    pub fn new<I: Individual>(
         configuration: String,
         iteration: u32,
         temperature: Temperature,
-        individual: VecIndividual,
-        problem: &dyn Problem,
+        individual: I,
+        problem: &dyn Problem<I>,
         best_fitness: Fitness,
     ) -> Self {
-        let fitness = problem
-            .eval(&individual)
+        let fitness = individual
+            .fitness(problem)
             .expect("Failed to calculate fitness");
         SimulatedAnnealingIterationInfo {
             configuration,
@@ -215,8 +277,8 @@ impl From<&SimulatedAnnealingIterationInfo> for CSVEntry {
             val.configuration.to_string(),
             val.iteration.to_string(),
             val.temperature.to_string(),
-            inverse_fitness(val.best_fitness).to_string(),
-            inverse_fitness(val.current_fitness).to_string(),
+            val.best_fitness.to_string(),
+            val.current_fitness.to_string(),
         ])
     }
 }
@@ -12,11 +12,13 @@ pub struct LinearCoolingSchedule {
     temperature: Temperature,
     iteration: u32,
     max_iteration: u32,
+    initial_temperature: Temperature,
 }
 
 impl CoolingSchedule for LinearCoolingSchedule {
     fn cooldown(&mut self) {
-        self.temperature = 1f32 - ((self.iteration + 1) as f32 / (self.max_iteration as f32));
+        self.temperature = self.initial_temperature
+            * (1f32 - ((self.iteration + 1) as f32 / (self.max_iteration as f32)));
         self.iteration += 1;
     }
 
@@ -29,11 +31,17 @@ impl CoolingSchedule for LinearCoolingSchedule {
     }
 }
 impl LinearCoolingSchedule {
+    // Cools down from 1 to 0
     pub fn new(max_iteration: u32) -> Self {
+        LinearCoolingSchedule::with_initial_temperature(max_iteration, 1f32)
+    }
+
+    pub fn with_initial_temperature(max_iteration: u32, initial_temperature: Temperature) -> Self {
         LinearCoolingSchedule {
             temperature: 0f32,
             iteration: 0,
             max_iteration,
+            initial_temperature,
         }
     }
 }
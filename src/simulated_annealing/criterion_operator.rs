@@ -1,11 +1,17 @@
-use rand::{thread_rng, Rng};
+use rand::{Rng, RngCore};
 
 use crate::individual::Fitness;
 
 use super::algorithm::Temperature;
 
 pub trait CriterionOperator {
-    fn criterion(&self, fitness_diff: Fitness, temperature: Temperature) -> bool;
+    // Whether to accept a neighbor worse than the current solution by fitness_diff
+    fn criterion(
+        &self,
+        fitness_diff: Fitness,
+        temperature: Temperature,
+        rng: &mut dyn RngCore,
+    ) -> bool;
     fn name(&self) -> String;
 }
 
@@ -13,12 +19,14 @@ pub trait CriterionOperator {
 pub struct BoltzmanProbabilityCriterionOperator {}
 
 impl CriterionOperator for BoltzmanProbabilityCriterionOperator {
-    fn criterion(&self, fitness_diff: Fitness, temperature: Temperature) -> bool {
-        let rand: f32 = thread_rng().gen_range(0f32..1f32);
-        let fitness_diff_by_temperature = -(fitness_diff * 0.01) / temperature;
-        println!("Diff {:.40}", fitness_diff);
-        println!("Temp {:.40}", temperature);
-        println!("By {:.40}", fitness_diff_by_temperature.exp());
+    fn criterion(
+        &self,
+        fitness_diff: Fitness,
+        temperature: Temperature,
+        rng: &mut dyn RngCore,
+    ) -> bool {
+        let rand: Fitness = rng.gen_range(0.0..1.0);
+        let fitness_diff_by_temperature = -fitness_diff / temperature as Fitness;
         rand < fitness_diff_by_temperature.exp()
     }
 
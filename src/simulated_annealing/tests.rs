@@ -8,9 +8,7 @@ use crate::{
 
 use super::{
     algorithm::SimulatedAnnealingBuilder,
-    cooling_schedule::{
-        ExponentialCoolingSchedule, ExponentialCoolingScheduleBuilder, LinearCoolingSchedule,
-    },
+    cooling_schedule::{ExponentialCoolingScheduleBuilder, LinearCoolingSchedule},
     criterion_operator::BoltzmanProbabilityCriterionOperator,
 };
 
@@ -28,7 +26,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -45,7 +43,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(0.5f32)
+                        .initial_temperature(50f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -59,7 +57,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(0.1f32)
+                        .initial_temperature(10f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -73,7 +71,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -87,7 +85,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -101,7 +99,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -115,7 +113,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -129,7 +127,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.80f32)
                         .build()?,
                 ))
@@ -143,7 +141,7 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(0.5f32)
+                        .initial_temperature(50f32)
                         .cooling_factor(0.80f32)
                         .build()?,
                 ))
@@ -155,7 +153,9 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
         Box::new(
             SimulatedAnnealingBuilder::default()
                 .iterations(10000)
-                .cooling_schedule(Box::new(LinearCoolingSchedule::new(10000)))
+                .cooling_schedule(Box::new(LinearCoolingSchedule::with_initial_temperature(
+                    10000, 100f32,
+                )))
                 .neighbor_operator(Box::new(SwapNeighborhoodOperator::new(10)))
                 .criterion_operator(Box::new(BoltzmanProbabilityCriterionOperator {}))
                 .logger(Box::new(CSVLogger::new(instance, None)))
@@ -164,7 +164,9 @@ pub fn get_simulated_annealing_config(instance: &str) -> Result<Vec<Box<dyn Solv
         Box::new(
             SimulatedAnnealingBuilder::default()
                 .iterations(10000)
-                .cooling_schedule(Box::new(LinearCoolingSchedule::new(10000)))
+                .cooling_schedule(Box::new(LinearCoolingSchedule::with_initial_temperature(
+                    10000, 100f32,
+                )))
                 .neighbor_operator(Box::new(InverseNeighborhoodOperator::new(10)))
                 .criterion_operator(Box::new(BoltzmanProbabilityCriterionOperator {}))
                 .logger(Box::new(CSVLogger::new(instance, None)))
@@ -187,7 +189,7 @@ pub fn get_simulated_annealing_best_three(instance: &str) -> Result<Vec<Box<dyn
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.995f32)
                         .build()?,
                 ))
@@ -204,7 +206,7 @@ pub fn get_simulated_annealing_best_three(instance: &str) -> Result<Vec<Box<dyn
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.99f32)
                         .build()?,
                 ))
@@ -218,7 +220,7 @@ pub fn get_simulated_annealing_best_three(instance: &str) -> Result<Vec<Box<dyn
                 .iterations(10000)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.999f32)
                         .build()?,
                 ))
@@ -1,10 +1,21 @@
 use anyhow::Result;
+use rand::Rng;
 
 use crate::{
     individual::{Fitness, VecIndividual},
     problem::Problem,
 };
 
-pub trait Solver {
-    fn solve(&mut self, problem: &dyn Problem) -> Result<(Fitness, VecIndividual)>;
+// Every random choice of a solver run is drawn from a generator seeded with it,
+// so the same seed always gives the same result. Each run draws the seed of the next
+// one from that generator, so repeated runs differ but are still reproducible.
+pub type Seed = u64;
+
+pub trait Solver<I = VecIndividual> {
+    fn solve(&mut self, problem: &dyn Problem<I>) -> Result<(Fitness, I)>;
+}
+
+// Used by builders without a configured seed, the solvers still log it so the run can be repeated
+pub fn random_seed() -> Seed {
+    rand::thread_rng().gen()
 }
@@ -1,29 +1,34 @@
 use anyhow::{Context, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
-    individual::{Fitness, VecIndividual},
-    logger::{inverse_fitness, CSVEntry, PersistableLogger},
-    population::Population,
-    problem::Problem,
-    solver::Solver,
+    evaluation::Evaluation,
+    individual::{Fitness, Individual, VecIndividual},
+    logger::{CSVEntry, PersistableLogger},
+    problem::{ObjectiveSense, Problem},
+    solver::{random_seed, Seed, Solver},
+    termination::{EvaluationCounter, Progress, TerminationCriterion},
 };
 
-use crate::neighbor::NeighborOperator;
+use crate::neighbor::{scored_moves, NeighborOperator};
 
-pub struct TabuSearch {
-    iterations: u32,
+pub struct TabuSearch<I = VecIndividual> {
+    termination: TerminationCriterion,
     tabu_list_size: usize,
     logger: Box<dyn PersistableLogger<IterationInfo>>,
-    neighborhood_operator: Box<dyn NeighborOperator>,
+    neighborhood_operator: Box<dyn NeighborOperator<I>>,
+    evaluation: Evaluation,
+    seed: Seed,
 }
 
-impl TabuSearch {
+impl<I: Individual> TabuSearch<I> {
     fn configuration_name(&self) -> String {
         format!(
-            "iterations: {}, tabu_size: {}, neighborhood_operator: {}",
-            self.iterations,
+            "{}, tabu_size: {}, neighborhood_operator: {}, seed: {}",
+            self.termination.name(),
             self.tabu_list_size,
-            self.neighborhood_operator.name()
+            self.neighborhood_operator.name(),
+            self.seed
         )
     }
 }
@@ -44,13 +49,22 @@ impl IterationInfo {
         configuration: String,
         iteration: u32,
         tabu_list_size: usize,
-        neighbours: Population,
-        problem: &dyn Problem,
+        neighbours_fitness: &[Fitness],
         best_fitness: Fitness,
+        sense: ObjectiveSense,
     ) -> Self {
-        let (_, current_fitness) = neighbours.highest_fitness(problem);
-        let (_, worst_fitness) = neighbours.lowest_fitness(problem);
-        let average_fitness = neighbours.average_fitness(problem);
+        let current_fitness = neighbours_fitness
+            .iter()
+            .copied()
+            .max_by(|a, b| sense.compare(*a, *b))
+            .unwrap_or(sense.worst());
+        let worst_fitness = neighbours_fitness
+            .iter()
+            .copied()
+            .min_by(|a, b| sense.compare(*a, *b))
+            .unwrap_or(sense.worst());
+        let average_fitness =
+            neighbours_fitness.iter().sum::<Fitness>() / neighbours_fitness.len() as Fitness;
         IterationInfo {
             configuration,
             iteration,
@@ -69,31 +83,49 @@ impl From<&IterationInfo> for CSVEntry {
             val.configuration.to_string(),
             val.iteration.to_string(),
             val.tabu_list_size.to_string(),
-            inverse_fitness(val.best_fitness).to_string(),
-            inverse_fitness(val.average_fitness).to_string(),
-            inverse_fitness(val.worst_fitness).to_string(),
-            inverse_fitness(val.current_fitness).to_string(),
+            val.best_fitness.to_string(),
+            val.average_fitness.to_string(),
+            val.worst_fitness.to_string(),
+            val.current_fitness.to_string(),
         ])
     }
 }
 
-impl Solver for TabuSearch {
-    fn solve(&mut self, problem: &dyn Problem) -> Result<(Fitness, VecIndividual)> {
-        let mut best_solution = problem.random_individual();
-        let mut tabu_list: Vec<VecIndividual> = Vec::new();
-        let mut best_fitness: Fitness = -f32::INFINITY;
-
-        for iteration in 0..self.iterations {
-            let neighbors = self.neighborhood_operator.get_neighborhood(&best_solution);
-            let population = Population::new(
-                neighbors
-                    .into_iter()
-                    .filter(|neighbor| !tabu_list.contains(neighbor))
-                    .collect(),
-            );
-
-            let (current_solution, current_solution_fitness) = population.highest_fitness(problem);
-            if current_solution_fitness > best_fitness {
+impl<I: Individual> Solver<I> for TabuSearch<I> {
+    fn solve(&mut self, problem: &dyn Problem<I>) -> Result<(Fitness, I)> {
+        let problem = &EvaluationCounter::new(problem);
+        let mut progress = Progress::new(problem.sense());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut best_solution = problem.random_individual(&mut rng);
+        let mut tabu_list: Vec<I> = Vec::new();
+        let sense = problem.sense();
+        let mut best_fitness: Fitness = sense.worst();
+
+        while !self.termination.is_met(&progress) {
+            let iteration = progress.iterations() as u32;
+            // Neighbors are scored by their moves, only the chosen one is created
+            let steps = self
+                .neighborhood_operator
+                .get_moves(&best_solution, &mut rng);
+            let scored = scored_moves(problem, &best_solution, steps, &self.evaluation)?;
+            let neighbors_fitness: Vec<Fitness> =
+                scored.iter().map(|(_, fitness)| *fitness).collect();
+
+            let current_solution = scored
+                .iter()
+                .map(|(step, _)| {
+                    let mut neighbor = best_solution.clone();
+                    neighbor.apply_move(step);
+                    neighbor
+                })
+                .find(|neighbor| !tabu_list.contains(neighbor));
+            let Some(current_solution) = current_solution else {
+                progress.record(best_fitness, problem.evaluations());
+                continue;
+            };
+            let current_solution_fitness = current_solution.fitness(problem)?;
+
+            if sense.is_better(current_solution_fitness, best_fitness) {
                 best_fitness = current_solution_fitness;
                 best_solution = current_solution.clone();
             }
@@ -107,27 +139,45 @@ impl Solver for TabuSearch {
                 self.configuration_name(),
                 iteration,
                 tabu_list.len(),
-                population,
-                problem,
+                &neighbors_fitness,
                 best_fitness,
+                sense,
             ));
+            progress.record(best_fitness, problem.evaluations());
         }
 
+        self.seed = rng.gen();
         self.logger.flush()?;
 
         Ok((best_fitness, best_solution))
     }
 }
 
-#[derive(Default)]
-pub struct TabuSearchBuilder {
+pub struct TabuSearchBuilder<I = VecIndividual> {
     iterations: Option<u32>,
+    termination: Option<TerminationCriterion>,
     tabu_list_size: Option<usize>,
     logger: Option<Box<dyn PersistableLogger<IterationInfo>>>,
-    neighborhood_operator: Option<Box<dyn NeighborOperator>>,
+    neighborhood_operator: Option<Box<dyn NeighborOperator<I>>>,
+    evaluation: Option<Evaluation>,
+    seed: Option<Seed>,
+}
+
+impl<I> Default for TabuSearchBuilder<I> {
+    fn default() -> Self {
+        TabuSearchBuilder {
+            iterations: None,
+            termination: None,
+            tabu_list_size: None,
+            logger: None,
+            neighborhood_operator: None,
+            evaluation: None,
+            seed: None,
+        }
+    }
 }
 
-impl TabuSearchBuilder {
+impl<I> TabuSearchBuilder<I> {
     pub fn iterations(mut self, iterations: u32) -> Self {
         self.iterations = Some(iterations);
         self
@@ -143,13 +193,33 @@ impl TabuSearchBuilder {
         self
     }
 
-    pub fn neighborhood_operator(mut self, neighborhood: Box<dyn NeighborOperator>) -> Self {
+    pub fn neighborhood_operator(mut self, neighborhood: Box<dyn NeighborOperator<I>>) -> Self {
         self.neighborhood_operator = Some(neighborhood);
         self
     }
-    pub fn build(self) -> Result<TabuSearch> {
+
+    pub fn termination(mut self, termination: TerminationCriterion) -> Self {
+        self.termination = Some(termination);
+        self
+    }
+
+    pub fn evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = Some(evaluation);
+        self
+    }
+
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Result<TabuSearch<I>> {
         Ok(TabuSearch {
-            iterations: self.iterations.context("Missing iterations parameters")?,
+            termination: TerminationCriterion::with_iterations(
+                self.termination,
+                self.iterations.map(u64::from),
+            )
+            .context("Missing iterations parameters")?,
             tabu_list_size: self
                 .tabu_list_size
                 .context("Missing tabu list size parameter")?,
@@ -157,6 +227,8 @@ impl TabuSearchBuilder {
             neighborhood_operator: self
                 .neighborhood_operator
                 .context("No neighborhood operator")?,
+            evaluation: self.evaluation.unwrap_or_default(),
+            seed: self.seed.unwrap_or_else(random_seed),
         })
     }
     //}
@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use rand::RngCore;
+
+use crate::{
+    individual::{Fitness, Individual},
+    problem::{ObjectiveSense, Problem},
+    problem_loader::best_known::BestKnownSolution,
+};
+
+#[cfg(test)]
+mod tests;
+
+// When a solver stops, checked before every generation or iteration
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminationCriterion {
+    // Generations of an evolutionary algorithm, iterations of the other solvers
+    Iterations(u64),
+    Time(Duration),
+    Evaluations(u64),
+    // Iterations without improving the best objective value
+    Stagnation(u64),
+    // Best objective value at least as good as the target
+    Target(Fitness),
+    Any(Vec<TerminationCriterion>),
+    All(Vec<TerminationCriterion>),
+}
+
+impl TerminationCriterion {
+    // Stops within the given percentage gap of the best known cost, if the cost is known
+    pub fn within_gap(best_known: &BestKnownSolution, gap: Fitness) -> Option<Self> {
+        best_known
+            .cost
+            .map(|cost| TerminationCriterion::Target(cost * (1.0 + gap / 100.0)))
+    }
+
+    pub fn or(self, other: TerminationCriterion) -> Self {
+        match self {
+            TerminationCriterion::Any(mut criteria) => {
+                criteria.push(other);
+                TerminationCriterion::Any(criteria)
+            }
+            criterion => TerminationCriterion::Any(vec![criterion, other]),
+        }
+    }
+
+    pub fn and(self, other: TerminationCriterion) -> Self {
+        match self {
+            TerminationCriterion::All(mut criteria) => {
+                criteria.push(other);
+                TerminationCriterion::All(criteria)
+            }
+            criterion => TerminationCriterion::All(vec![criterion, other]),
+        }
+    }
+
+    // Builders accept a fixed number of iterations next to the criterion, the solver stops
+    // at whichever comes first
+    pub fn with_iterations(criterion: Option<Self>, iterations: Option<u64>) -> Option<Self> {
+        match (criterion, iterations) {
+            (Some(criterion), Some(iterations)) => {
+                Some(criterion.or(TerminationCriterion::Iterations(iterations)))
+            }
+            (Some(criterion), None) => Some(criterion),
+            (None, iterations) => iterations.map(TerminationCriterion::Iterations),
+        }
+    }
+
+    pub fn is_met(&self, progress: &Progress) -> bool {
+        match self {
+            TerminationCriterion::Iterations(iterations) => progress.iterations >= *iterations,
+            TerminationCriterion::Time(duration) => progress.elapsed() >= *duration,
+            TerminationCriterion::Evaluations(evaluations) => progress.evaluations >= *evaluations,
+            TerminationCriterion::Stagnation(iterations) => progress.stagnation() >= *iterations,
+            TerminationCriterion::Target(target) => progress
+                .best_fitness
+                .is_some_and(|best| !progress.sense.is_better(*target, best)),
+            TerminationCriterion::Any(criteria) => {
+                criteria.iter().any(|criterion| criterion.is_met(progress))
+            }
+            TerminationCriterion::All(criteria) => {
+                criteria.iter().all(|criterion| criterion.is_met(progress))
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let names = |criteria: &[TerminationCriterion]| {
+            criteria
+                .iter()
+                .map(|criterion| criterion.name())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        match self {
+            TerminationCriterion::Iterations(iterations) => format!("iterations: {}", iterations),
+            TerminationCriterion::Time(duration) => format!("time: {:?}", duration),
+            TerminationCriterion::Evaluations(evaluations) => {
+                format!("evaluations: {}", evaluations)
+            }
+            TerminationCriterion::Stagnation(iterations) => format!("stagnation: {}", iterations),
+            TerminationCriterion::Target(target) => format!("target: {}", target),
+            TerminationCriterion::Any(criteria) => format!("any of ({})", names(criteria)),
+            TerminationCriterion::All(criteria) => format!("all of ({})", names(criteria)),
+        }
+    }
+}
+
+// State of a run the termination criteria are checked against
+#[derive(Debug, Clone)]
+pub struct Progress {
+    sense: ObjectiveSense,
+    started: Instant,
+    iterations: u64,
+    evaluations: u64,
+    best_fitness: Option<Fitness>,
+    last_improvement: u64,
+}
+
+impl Progress {
+    pub fn new(sense: ObjectiveSense) -> Self {
+        Progress {
+            sense,
+            started: Instant::now(),
+            iterations: 0,
+            evaluations: 0,
+            best_fitness: None,
+            last_improvement: 0,
+        }
+    }
+
+    // Called after every generation or iteration with the best objective value found so far
+    pub fn record(&mut self, best_fitness: Fitness, evaluations: u64) {
+        self.iterations += 1;
+        self.evaluations = evaluations;
+
+        let improved = self
+            .best_fitness
+            .is_none_or(|best| self.sense.is_better(best_fitness, best));
+        if improved {
+            self.best_fitness = Some(best_fitness);
+            self.last_improvement = self.iterations;
+        }
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn stagnation(&self) -> u64 {
+        self.iterations - self.last_improvement
+    }
+}
+
+// Counts the evaluations of the wrapped problem, including the neighbors scored by their moves
+pub struct EvaluationCounter<'a, I: Individual> {
+    problem: &'a dyn Problem<I>,
+    evaluations: AtomicU64,
+}
+
+impl<'a, I: Individual> EvaluationCounter<'a, I> {
+    pub fn new(problem: &'a dyn Problem<I>) -> Self {
+        EvaluationCounter {
+            problem,
+            evaluations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations.load(Ordering::Relaxed)
+    }
+
+    fn count(&self, evaluations: usize) {
+        self.evaluations
+            .fetch_add(evaluations as u64, Ordering::Relaxed);
+    }
+}
+
+impl<I: Individual> Problem<I> for EvaluationCounter<'_, I> {
+    fn eval(&self, individual: &I) -> Result<Fitness> {
+        self.count(1);
+        self.problem.eval(individual)
+    }
+
+    fn random_individual(&self, rng: &mut dyn RngCore) -> I {
+        self.problem.random_individual(rng)
+    }
+
+    fn serialize_indiviual(&self, individual: &I) -> String {
+        self.problem.serialize_indiviual(individual)
+    }
+
+    fn sense(&self) -> ObjectiveSense {
+        self.problem.sense()
+    }
+
+    fn eval_move(&self, individual: &I, step: &I::Move) -> Result<Fitness> {
+        self.count(1);
+        self.problem.eval_move(individual, step)
+    }
+
+    fn eval_moves(&self, individual: &I, steps: &[I::Move]) -> Result<Vec<Fitness>> {
+        self.count(steps.len());
+        self.problem.eval_moves(individual, steps)
+    }
+}
@@ -0,0 +1,189 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use crate::{
+    evolutionary_algorithm::{
+        algorithm::{EvolutionaryAlgorithmBuilder, GenerationInfo},
+        crossover::{CrossoverOperator, UniformCrossover},
+        mutation::BitFlipMutation,
+        selection::TournamentSelector,
+    },
+    individual::Fitness,
+    knapsack::{Item, KnapsackProblem},
+    logger::{CSVEntry, NoopLogger, PersistableLogger},
+    neighbor::SwapNeighborhoodOperator,
+    problem::{ObjectiveSense, Problem},
+    problem_loader::{best_known::BestKnownSolution, CVRProblem},
+    simulated_annealing::{
+        algorithm::SimulatedAnnealingBuilder, cooling_schedule::ExponentialCoolingScheduleBuilder,
+        criterion_operator::BoltzmanProbabilityCriterionOperator,
+    },
+    solver::Solver,
+    tabu_search::algorithm::TabuSearchBuilder,
+};
+
+use super::{EvaluationCounter, Progress, TerminationCriterion};
+
+#[test]
+fn criteria_follow_the_progress() {
+    let mut progress = Progress::new(ObjectiveSense::Minimize);
+    let target = TerminationCriterion::within_gap(
+        &BestKnownSolution {
+            cost: Some(100.0),
+            ..BestKnownSolution::default()
+        },
+        1.0,
+    )
+    .unwrap();
+    let budget = TerminationCriterion::Iterations(4).or(TerminationCriterion::Evaluations(50));
+    let stagnation =
+        TerminationCriterion::Stagnation(2).and(TerminationCriterion::Time(Duration::ZERO));
+
+    for (best_fitness, evaluations) in [(110.0, 10), (105.0, 20), (105.0, 30)] {
+        progress.record(best_fitness, evaluations);
+    }
+    assert!(!target.is_met(&progress));
+    assert!(!budget.is_met(&progress));
+    assert!(!stagnation.is_met(&progress));
+
+    progress.record(104.0, 50);
+    assert!(budget.is_met(&progress));
+    assert!(!stagnation.is_met(&progress));
+
+    progress.record(104.0, 60);
+    progress.record(101.0, 70);
+    assert!(target.is_met(&progress));
+    assert_eq!(progress.stagnation(), 0);
+}
+
+#[test]
+fn tabu_search_stops_at_the_evaluation_budget() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/A-n32-k5.txt")).unwrap();
+    let counter = EvaluationCounter::new(&problem);
+    let mut tabu_search = TabuSearchBuilder::default()
+        .tabu_list_size(5)
+        .neighborhood_operator(Box::new(SwapNeighborhoodOperator::new(20)))
+        .logger(Box::new(NoopLogger))
+        .termination(TerminationCriterion::Evaluations(200))
+        .build()
+        .unwrap();
+
+    tabu_search.solve(&counter).unwrap();
+
+    // The budget is checked between iterations, each of them scores a whole neighborhood
+    assert!((200..200 + 20 + 2).contains(&counter.evaluations()));
+}
+
+#[test]
+fn evolutionary_algorithm_stops_at_the_target() {
+    let items = [(60, 10), (100, 20), (120, 30), (10, 25)]
+        .iter()
+        .map(|(value, weight)| Item {
+            value: *value,
+            weight: *weight,
+        })
+        .collect();
+    let problem = KnapsackProblem::new(items, 50);
+    let mut algorithm = EvolutionaryAlgorithmBuilder::new()
+        .population_size(20)
+        .generations(u16::MAX)
+        .crossover_prob(0.7)
+        .mutation_prob(0.3)
+        .crossover_operator(CrossoverOperator::SingleChildCrossoverOperator(Box::new(
+            UniformCrossover {},
+        )))
+        .mutation_operator(Box::new(BitFlipMutation {}))
+        .selection_operator(Box::new(TournamentSelector::new(3)))
+        .logger(Box::new(NoopLogger))
+        .termination(TerminationCriterion::Target(220.0))
+        .seed(1)
+        .build()
+        .unwrap();
+
+    let (fitness, solution) = algorithm.solve(&problem).unwrap();
+
+    assert_eq!(fitness, 220.0);
+    assert_eq!(problem.eval(&solution).unwrap(), 220.0);
+}
+
+#[test]
+fn simulated_annealing_returns_the_best_individual() {
+    let problem = CVRProblem::parse(include_str!("../problem-instances/A-n32-k5.txt")).unwrap();
+    let mut previous = Fitness::INFINITY;
+
+    // A hot schedule accepts most worse neighbors, so the last individual is rarely the best.
+    // Runs sharing the seed share the walk, longer ones can only find better individuals.
+    for iterations in [25, 50, 100, 200] {
+        let mut algorithm = SimulatedAnnealingBuilder::default()
+            .neighbor_operator(Box::new(SwapNeighborhoodOperator::new(5)))
+            .cooling_schedule(Box::new(
+                ExponentialCoolingScheduleBuilder::default()
+                    .initial_temperature(1000f32)
+                    .cooling_factor(1f32)
+                    .build()
+                    .unwrap(),
+            ))
+            .criterion_operator(Box::new(BoltzmanProbabilityCriterionOperator {}))
+            .logger(Box::new(NoopLogger))
+            .termination(TerminationCriterion::Iterations(iterations))
+            .seed(7)
+            .build()
+            .unwrap();
+
+        let (fitness, solution) = algorithm.solve(&problem).unwrap();
+
+        assert_eq!(problem.eval(&solution).unwrap(), fitness);
+        assert!(fitness <= previous);
+        previous = fitness;
+    }
+}
+
+// Keeps the best fitness of every logged generation
+struct BestFitnessLogger(Rc<RefCell<Vec<Fitness>>>);
+
+impl PersistableLogger<GenerationInfo> for BestFitnessLogger {
+    fn log(&mut self, entry: GenerationInfo) {
+        let row = CSVEntry::from(&entry).to_row();
+        let best_fitness = row.split(';').nth(2).unwrap().parse().unwrap();
+        self.0.borrow_mut().push(best_fitness);
+    }
+
+    fn flush(&mut self) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn evolutionary_algorithm_keeps_the_best_of_the_last_generation() {
+    let items = [(60, 10), (100, 20), (120, 30), (10, 25), (40, 15), (30, 5)]
+        .iter()
+        .map(|(value, weight)| Item {
+            value: *value,
+            weight: *weight,
+        })
+        .collect();
+    let problem = KnapsackProblem::new(items, 50);
+
+    for generations in 1..=10 {
+        let best_fitnesses = Rc::new(RefCell::new(Vec::new()));
+        let mut algorithm = EvolutionaryAlgorithmBuilder::new()
+            .population_size(4)
+            .generations(u16::MAX)
+            .crossover_prob(0.7)
+            .mutation_prob(0.3)
+            .crossover_operator(CrossoverOperator::SingleChildCrossoverOperator(Box::new(
+                UniformCrossover {},
+            )))
+            .mutation_operator(Box::new(BitFlipMutation {}))
+            .selection_operator(Box::new(TournamentSelector::new(2)))
+            .logger(Box::new(BestFitnessLogger(best_fitnesses.clone())))
+            .termination(TerminationCriterion::Iterations(generations))
+            .seed(3)
+            .build()
+            .unwrap();
+
+        let (fitness, solution) = algorithm.solve(&problem).unwrap();
+
+        assert_eq!(problem.eval(&solution).unwrap(), fitness);
+        assert!(best_fitnesses.borrow().iter().all(|best| *best <= fitness));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::problem::Problem;
+use crate::problem_loader::{
+    distance::{distance_matrix, DistanceFunction, DistancePrecision},
+    parser::{ParseError, TsplibInstance},
+    Coordinates,
+};
+use anyhow::{anyhow, Context, Result};
+use rand::RngCore;
+use std::fmt::Write;
+use std::fs::read_to_string;
+
+#[cfg(test)]
+mod tests;
+
+// Symmetric or asymmetric TSP read from a TSPLIB .tsp/.atsp file, genes are 0-based node indexes
+#[derive(Debug, Default)]
+pub struct TSPProblem {
+    name: String,
+    comment: String,
+    dimension: usize,
+    distance_function: DistanceFunction,
+    distance_precision: DistancePrecision,
+    all_nodes: Vec<Coordinates>,
+    distances: Vec<Vec<Fitness>>,
+}
+
+impl Problem for TSPProblem {
+    fn random_individual(&self, rng: &mut dyn RngCore) -> VecIndividual {
+        let nodes: Vec<Gene> = (0..self.dimension as Gene).collect();
+        let mut left_nodes = VecIndividual::from(&nodes);
+        let mut selected_nodes: VecIndividual = VecIndividual::new();
+
+        for _ in &nodes {
+            let selected_gene = left_nodes.random_gene(rng);
+            selected_nodes.add_gene(selected_gene);
+            left_nodes.remove_gene(selected_gene)
+        }
+
+        selected_nodes
+    }
+
+    fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
+        self.tour_length(individual)
+    }
+
+    // TSPLIB TOUR_SECTION with 1-based nodes
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        let mut tour = String::from("TOUR_SECTION\n");
+        for gene in individual.genes() {
+            let _ = writeln!(tour, "{}", gene + 1);
+        }
+        tour.push_str("-1\nEOF\n");
+        tour
+    }
+}
+
+impl TSPProblem {
+    pub fn parse(content: &str) -> Result<TSPProblem, ParseError> {
+        TsplibInstance::parse_graph(content).map(TSPProblem::from_instance)
+    }
+
+    pub fn from_file(path: &str) -> Result<TSPProblem> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        TSPProblem::parse(&content).with_context(|| format!("Failed to parse {}", path))
+    }
+
+    fn from_instance(instance: TsplibInstance) -> TSPProblem {
+        let mut problem = TSPProblem {
+            name: instance.name,
+            comment: instance.comment,
+            dimension: instance.dimension.unwrap_or_default(),
+            distance_function: instance.distance_function,
+            all_nodes: instance.node_coordinates.into_iter().flatten().collect(),
+            distances: instance.edge_weights.unwrap_or_default(),
+            ..TSPProblem::default()
+        };
+        problem.precalculate_distances();
+        problem
+    }
+
+    // Length of the closed tour, returning from the last node to the first one
+    pub fn tour_length(&self, individual: &VecIndividual) -> Result<Fitness> {
+        let genes = individual.genes();
+        if genes.len() != self.dimension {
+            return Err(anyhow!(
+                "Tour visits {} nodes, but the instance has {}",
+                genes.len(),
+                self.dimension
+            ));
+        }
+
+        genes
+            .iter()
+            .zip(genes.iter().cycle().skip(1))
+            .map(|(from, to)| self.distance(from, to))
+            .sum()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    pub fn distance_precision(&self) -> DistancePrecision {
+        self.distance_precision
+    }
+
+    // Switches between exact distances and the TSPLIB rounded ones used by published optimal values
+    pub fn set_distance_precision(&mut self, precision: DistancePrecision) {
+        self.distance_precision = precision;
+        self.precalculate_distances();
+    }
+
+    pub fn distance(&self, node_a: &Gene, node_b: &Gene) -> Result<Fitness> {
+        self.distances
+            .get(*node_a as usize)
+            .and_then(|row| row.get(*node_b as usize))
+            .copied()
+            .ok_or(anyhow!("Failed to get distance, invalid node indexes"))
+    }
+
+    // Distances of EXPLICIT instances are read straight from the file and never recalculated
+    fn precalculate_distances(&mut self) {
+        if self.distance_function == DistanceFunction::Explicit {
+            return;
+        }
+
+        self.distances = distance_matrix(
+            self.distance_function,
+            &self.all_nodes,
+            self.distance_precision,
+        );
+    }
+}
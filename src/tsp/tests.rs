@@ -0,0 +1,90 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    evolutionary_algorithm::{
+        crossover::{OrderedCrossover, SingleChildCrossoverOperator},
+        mutation::{InverseMutation, Mutation},
+    },
+    individual::VecIndividual,
+    problem::Problem,
+    problem_loader::distance::DistancePrecision,
+};
+
+use super::TSPProblem;
+
+#[test]
+fn burma14_optimal_tour_length() {
+    let mut problem = TSPProblem::parse(include_str!("../problem-instances/burma14.tsp")).unwrap();
+    problem.set_distance_precision(DistancePrecision::Rounded);
+    let optimal_tour: Vec<u16> = [1, 2, 14, 3, 4, 5, 6, 12, 7, 13, 8, 11, 9, 10]
+        .iter()
+        .map(|node| node - 1)
+        .collect();
+
+    assert_eq!(problem.name(), "burma14");
+    assert_eq!(problem.dimension(), 14);
+    assert_eq!(
+        problem
+            .tour_length(&VecIndividual::from(optimal_tour))
+            .unwrap(),
+        3323.0
+    );
+}
+
+#[test]
+fn explicit_instance_without_demands() {
+    let content = "NAME : tiny\nTYPE : ATSP\nDIMENSION : 3\nEDGE_WEIGHT_TYPE : EXPLICIT\n\
+        EDGE_WEIGHT_FORMAT : FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0 1 9\n9 0 2\n3 9 0\nEOF\n";
+    let problem = TSPProblem::parse(content).unwrap();
+
+    assert_eq!(
+        problem
+            .tour_length(&VecIndividual::from(vec![0, 1, 2]))
+            .unwrap(),
+        6.0
+    );
+    assert!(problem
+        .tour_length(&VecIndividual::from(vec![0, 1]))
+        .is_err());
+    assert!(problem
+        .serialize_indiviual(&VecIndividual::from(vec![0, 1, 2]))
+        .starts_with("TOUR_SECTION\n1\n2\n3\n-1"));
+}
+
+#[test]
+fn permutation_operators_keep_tours_valid() {
+    let problem = TSPProblem::parse(include_str!("../problem-instances/burma14.tsp")).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let parents = (
+        problem.random_individual(&mut rng),
+        problem.random_individual(&mut rng),
+    );
+
+    let mut child = OrderedCrossover {}.crossover(&parents.0, &parents.1, &mut rng);
+    InverseMutation {}.mutate(&mut child, &mut rng);
+
+    let mut genes = child.genes().clone();
+    genes.sort();
+    assert_eq!(genes, (0..14).collect::<Vec<u16>>());
+    assert!(problem.eval(&child).unwrap() > 0.0);
+}
+
+#[test]
+fn inverse_mutation_reverses_one_segment() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..20 {
+        let mut individual = VecIndividual::from((0..10).collect::<Vec<u16>>());
+        InverseMutation {}.mutate(&mut individual, &mut rng);
+
+        let genes = individual.genes();
+        let start = (0..10)
+            .position(|index| genes[index] != index as u16)
+            .unwrap();
+        let end = (0..10)
+            .rposition(|index| genes[index] != index as u16)
+            .unwrap();
+        let segment: Vec<u16> = (start as u16..=end as u16).rev().collect();
+        assert_eq!(genes[start..=end], segment);
+    }
+}
@@ -1,38 +1,44 @@
 use anyhow::{Context, Result};
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 
 use crate::{
-    individual::{Fitness, VecIndividual},
-    logger::{inverse_fitness, CSVEntry, PersistableLogger},
-    population::Population,
-    problem::Problem,
+    evaluation::Evaluation,
+    individual::{Fitness, Individual, VecIndividual},
+    logger::{CSVEntry, PersistableLogger},
+    problem::{ObjectiveSense, Problem},
     simulated_annealing::{
         algorithm::Solution, cooling_schedule::CoolingSchedule,
         criterion_operator::CriterionOperator,
     },
-    solver::Solver,
+    solver::{random_seed, Seed, Solver},
+    termination::{EvaluationCounter, Progress, TerminationCriterion},
 };
 
-use crate::neighbor::NeighborOperator;
+use crate::neighbor::{scored_moves, NeighborOperator};
 
-pub struct TSSA {
-    iterations: u32,
+pub struct TSSA<I = VecIndividual> {
+    termination: TerminationCriterion,
     algorithm_switch_interval: u32,
     tabu_list_size: usize,
     logger: Box<dyn PersistableLogger<IterationInfo>>,
-    neighborhood_operator: Box<dyn NeighborOperator>,
+    neighborhood_operator: Box<dyn NeighborOperator<I>>,
+    evaluation: Evaluation,
     criterion_operator: Box<dyn CriterionOperator>,
     cooling_schedule: Box<dyn CoolingSchedule>,
+    seed: Seed,
 }
 
-impl TSSA {
+impl<I: Individual> TSSA<I> {
     fn configuration_name(&self) -> String {
         format!(
-            "tabu_size: {}, neighborhood_operator: {}, criterion: {}, cooling_schedule: {}, switch_interval: {}",
+            "{}, tabu_size: {}, neighborhood_operator: {}, criterion: {}, cooling_schedule: {}, switch_interval: {}, seed: {}",
+            self.termination.name(),
             self.tabu_list_size,
             self.neighborhood_operator.name(),
             self.criterion_operator.name(),
             self.cooling_schedule.name(),
-            self.algorithm_switch_interval
+            self.algorithm_switch_interval,
+            self.seed
         )
     }
 }
@@ -51,13 +57,22 @@ impl IterationInfo {
     pub fn new(
         configuration: String,
         iteration: u32,
-        neighbours: Population,
-        problem: &dyn Problem,
+        neighbours_fitness: &[Fitness],
         best_fitness: Fitness,
+        sense: ObjectiveSense,
     ) -> Self {
-        let (_, current_fitness) = neighbours.highest_fitness(problem);
-        let (_, worst_fitness) = neighbours.lowest_fitness(problem);
-        let average_fitness = neighbours.average_fitness(problem);
+        let current_fitness = neighbours_fitness
+            .iter()
+            .copied()
+            .max_by(|a, b| sense.compare(*a, *b))
+            .unwrap_or(sense.worst());
+        let worst_fitness = neighbours_fitness
+            .iter()
+            .copied()
+            .min_by(|a, b| sense.compare(*a, *b))
+            .unwrap_or(sense.worst());
+        let average_fitness =
+            neighbours_fitness.iter().sum::<Fitness>() / neighbours_fitness.len() as Fitness;
         IterationInfo {
             configuration,
             iteration,
@@ -74,10 +89,10 @@ impl From<&IterationInfo> for CSVEntry {
         CSVEntry::from(vec![
             val.configuration.to_string(),
             val.iteration.to_string(),
-            inverse_fitness(val.best_fitness).to_string(),
-            inverse_fitness(val.average_fitness).to_string(),
-            inverse_fitness(val.worst_fitness).to_string(),
-            inverse_fitness(val.current_fitness).to_string(),
+            val.best_fitness.to_string(),
+            val.average_fitness.to_string(),
+            val.worst_fitness.to_string(),
+            val.current_fitness.to_string(),
         ])
     }
 }
@@ -87,21 +102,25 @@ enum CurrentAlgorithm {
     TS,
 }
 
-impl Solver for TSSA {
-    fn solve(&mut self, problem: &dyn Problem) -> Result<(Fitness, VecIndividual)> {
-        let mut best_solution = problem.random_individual();
-        let mut best_fitness: Fitness = -f32::INFINITY;
+impl<I: Individual> Solver<I> for TSSA<I> {
+    fn solve(&mut self, problem: &dyn Problem<I>) -> Result<(Fitness, I)> {
+        let problem = &EvaluationCounter::new(problem);
+        let mut progress = Progress::new(problem.sense());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut best_solution = problem.random_individual(&mut rng);
+        let mut best_fitness: Fitness = problem.sense().worst();
         let mut current_alg: CurrentAlgorithm = CurrentAlgorithm::TS;
 
-        for iteration in 0..self.iterations {
+        while !self.termination.is_met(&progress) {
             match current_alg {
                 CurrentAlgorithm::SA => {
                     let new_best = self
                         .solve_with_sa(
                             problem,
-                            iteration * self.algorithm_switch_interval,
+                            &mut progress,
                             best_solution.clone(),
                             best_fitness,
+                            &mut rng,
                         )
                         .unwrap();
                     current_alg = CurrentAlgorithm::TS;
@@ -113,9 +132,10 @@ impl Solver for TSSA {
                     let new_best = self
                         .solve_with_tabu(
                             problem,
-                            iteration * self.algorithm_switch_interval,
+                            &mut progress,
                             best_solution.clone(),
                             best_fitness,
+                            &mut rng,
                         )
                         .unwrap();
 
@@ -126,39 +146,55 @@ impl Solver for TSSA {
             }
         }
 
+        self.seed = rng.gen();
         self.logger.flush()?;
 
         Ok((best_fitness, best_solution))
     }
 }
 
-impl TSSA {
+impl<I: Individual> TSSA<I> {
     fn solve_with_tabu(
         &mut self,
-        problem: &dyn Problem,
-        initial_iteration: u32,
-        initial_solution: VecIndividual,
+        problem: &EvaluationCounter<I>,
+        progress: &mut Progress,
+        initial_solution: I,
         initial_fitness: Fitness,
-    ) -> Result<(Fitness, VecIndividual)> {
+        rng: &mut dyn RngCore,
+    ) -> Result<(Fitness, I)> {
         let mut best_solution = initial_solution;
-        let mut tabu_list: Vec<VecIndividual> = Vec::new();
+        let mut tabu_list: Vec<I> = Vec::new();
         let mut best_fitness: Fitness = initial_fitness;
 
-        for iteration in initial_iteration..(self.algorithm_switch_interval + initial_iteration) {
-            let neighbors = self.neighborhood_operator.get_neighborhood(&best_solution);
-            let population = Population::new(
-                neighbors
-                    .into_iter()
-                    .filter(|neighbor| !tabu_list.contains(neighbor))
-                    .collect(),
-            );
-            if population.number_of_solutions() == 0 {
-                continue;
+        for _ in 0..self.algorithm_switch_interval {
+            if self.termination.is_met(progress) {
+                break;
             }
+            let iteration = progress.iterations() as u32;
+            let steps = self.neighborhood_operator.get_moves(&best_solution, rng);
+            let scored = scored_moves(problem, &best_solution, steps, &self.evaluation)?;
+            let neighbors_fitness: Vec<Fitness> =
+                scored.iter().map(|(_, fitness)| *fitness).collect();
+
+            let current_solution = scored
+                .iter()
+                .map(|(step, _)| {
+                    let mut neighbor = best_solution.clone();
+                    neighbor.apply_move(step);
+                    neighbor
+                })
+                .find(|neighbor| !tabu_list.contains(neighbor));
+            let Some(current_solution) = current_solution else {
+                progress.record(best_fitness, problem.evaluations());
+                continue;
+            };
             println!("Iteration of TS {}", iteration);
 
-            let (current_solution, current_solution_fitness) = population.highest_fitness(problem);
-            if current_solution_fitness > best_fitness {
+            let current_solution_fitness = current_solution.fitness(problem)?;
+            if problem
+                .sense()
+                .is_better(current_solution_fitness, best_fitness)
+            {
                 best_fitness = current_solution_fitness;
                 best_solution = current_solution.clone();
             }
@@ -171,10 +207,11 @@ impl TSSA {
             self.logger.log(IterationInfo::new(
                 self.configuration_name(),
                 iteration,
-                population,
-                problem,
+                &neighbors_fitness,
                 best_fitness,
+                problem.sense(),
             ));
+            progress.record(best_fitness, problem.evaluations());
         }
 
         Ok((best_fitness, best_solution))
@@ -182,100 +219,134 @@ impl TSSA {
 
     fn solve_with_sa(
         &mut self,
-        problem: &dyn Problem,
-        initial_iteration: u32,
-        initial_solution: VecIndividual,
+        problem: &EvaluationCounter<I>,
+        progress: &mut Progress,
+        initial_solution: I,
         initial_fitness: Fitness,
-    ) -> Result<(Fitness, VecIndividual)> {
+        rng: &mut dyn RngCore,
+    ) -> Result<(Fitness, I)> {
         let mut solution = Solution {
-            fitness: initial_fitness,
+            best_individual: initial_solution.clone(),
             individual: initial_solution,
+            fitness: initial_fitness,
             best_fitness: initial_fitness,
         };
 
-        for i in initial_iteration..(self.algorithm_switch_interval + initial_iteration) {
-            solution = self.solution_iteration(solution, problem)?;
+        for _ in 0..self.algorithm_switch_interval {
+            if self.termination.is_met(progress) {
+                break;
+            }
+            let iteration = progress.iterations() as u32;
+            solution = self.solution_iteration(solution, problem, rng)?;
             self.decrease_temperature();
-            self.log(problem, &solution, i);
+            self.log(problem, &solution, iteration);
+            progress.record(solution.best_fitness, problem.evaluations());
         }
 
-        Ok((solution.best_fitness, solution.individual))
+        Ok((solution.best_fitness, solution.best_individual))
     }
 
     fn solution_iteration(
         &mut self,
-        solution: Solution,
-        problem: &dyn Problem,
-    ) -> Result<Solution> {
-        let new_solution = self.get_neighbor(&solution, problem)?;
-
-        if new_solution.fitness > solution.fitness {
-            return Ok(Solution {
-                fitness: new_solution.fitness,
-                best_fitness: solution.best_fitness.max(new_solution.fitness),
-                individual: new_solution.individual,
-            });
-        }
-
-        if self.secondary_acceptance_criteria(solution.fitness - new_solution.fitness) {
-            return Ok(Solution {
-                fitness: new_solution.fitness,
-                best_fitness: solution.best_fitness.max(new_solution.fitness),
-                individual: new_solution.individual,
-            });
+        solution: Solution<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<Solution<I>> {
+        let (individual, fitness) = self.get_neighbor(&solution, problem, rng)?;
+        let sense = problem.sense();
+        let accepted = sense.is_better(fitness, solution.fitness)
+            || self
+                .secondary_acceptance_criteria(sense.improvement(fitness, solution.fitness), rng);
+
+        if !accepted {
+            return Ok(solution);
         }
 
-        Ok(solution)
+        let (best_individual, best_fitness) = match sense.is_better(fitness, solution.best_fitness)
+        {
+            true => (individual.clone(), fitness),
+            false => (solution.best_individual, solution.best_fitness),
+        };
+        Ok(Solution {
+            individual,
+            fitness,
+            best_individual,
+            best_fitness,
+        })
     }
 
-    fn get_neighbor(&self, solution: &Solution, problem: &dyn Problem) -> Result<Solution> {
-        let neighbors = self
+    fn get_neighbor(
+        &self,
+        solution: &Solution<I>,
+        problem: &dyn Problem<I>,
+        rng: &mut dyn RngCore,
+    ) -> Result<(I, Fitness)> {
+        let steps = self
             .neighborhood_operator
-            .get_neighborhood(&solution.individual);
+            .get_moves(&solution.individual, rng);
+        let (step, _) = scored_moves(problem, &solution.individual, steps, &self.evaluation)?
+            .into_iter()
+            .next()
+            .context("Neighborhood is empty")?;
 
-        let population = Population::new(neighbors);
+        let mut individual = solution.individual.clone();
+        individual.apply_move(&step);
+        let fitness = individual.fitness(problem)?;
 
-        let (individual, fitness) = population.highest_fitness(problem);
-
-        Ok(Solution {
-            individual: individual.clone(),
-            fitness,
-            best_fitness: fitness,
-        })
+        Ok((individual, fitness))
     }
 
-    fn secondary_acceptance_criteria(&self, fitness_diff: Fitness) -> bool {
+    fn secondary_acceptance_criteria(&self, fitness_diff: Fitness, rng: &mut dyn RngCore) -> bool {
         self.criterion_operator
-            .criterion(fitness_diff, self.cooling_schedule.temperature())
+            .criterion(fitness_diff, self.cooling_schedule.temperature(), rng)
     }
 
     fn decrease_temperature(&mut self) {
         self.cooling_schedule.cooldown()
     }
 
-    fn log(&mut self, problem: &dyn Problem, solution: &Solution, iteration: u32) {
+    fn log(&mut self, problem: &dyn Problem<I>, solution: &Solution<I>, iteration: u32) {
         self.logger.log(IterationInfo::new(
             self.configuration_name(),
             iteration,
-            Population::new(vec![solution.individual.clone()]),
-            problem,
+            &[solution.fitness],
             solution.best_fitness,
+            problem.sense(),
         ));
     }
 }
 
-#[derive(Default)]
-pub struct TSSABuilder {
+pub struct TSSABuilder<I = VecIndividual> {
     iterations: Option<u32>,
+    termination: Option<TerminationCriterion>,
     tabu_list_size: Option<usize>,
     algorithm_switch_interval: Option<u32>,
     cooling_schedule: Option<Box<dyn CoolingSchedule>>,
     criterion_operator: Option<Box<dyn CriterionOperator>>,
     logger: Option<Box<dyn PersistableLogger<IterationInfo>>>,
-    neighborhood_operator: Option<Box<dyn NeighborOperator>>,
+    neighborhood_operator: Option<Box<dyn NeighborOperator<I>>>,
+    evaluation: Option<Evaluation>,
+    seed: Option<Seed>,
+}
+
+impl<I> Default for TSSABuilder<I> {
+    fn default() -> Self {
+        TSSABuilder {
+            iterations: None,
+            termination: None,
+            tabu_list_size: None,
+            algorithm_switch_interval: None,
+            cooling_schedule: None,
+            criterion_operator: None,
+            logger: None,
+            neighborhood_operator: None,
+            evaluation: None,
+            seed: None,
+        }
+    }
 }
 
-impl TSSABuilder {
+impl<I> TSSABuilder<I> {
     pub fn iterations(mut self, iterations: u32) -> Self {
         self.iterations = Some(iterations);
         self
@@ -291,7 +362,7 @@ impl TSSABuilder {
         self
     }
 
-    pub fn neighborhood_operator(mut self, neighborhood: Box<dyn NeighborOperator>) -> Self {
+    pub fn neighborhood_operator(mut self, neighborhood: Box<dyn NeighborOperator<I>>) -> Self {
         self.neighborhood_operator = Some(neighborhood);
         self
     }
@@ -311,21 +382,45 @@ impl TSSABuilder {
         self
     }
 
-    pub fn build(self) -> Result<TSSA> {
+    pub fn termination(mut self, termination: TerminationCriterion) -> Self {
+        self.termination = Some(termination);
+        self
+    }
+
+    pub fn evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = Some(evaluation);
+        self
+    }
+
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Result<TSSA<I>> {
+        let algorithm_switch_interval = self
+            .algorithm_switch_interval
+            .context("No algorithm switch interval")?;
+        // Every iteration runs one of the algorithms for the switch interval
+        let iterations = self
+            .iterations
+            .map(|iterations| iterations as u64 * algorithm_switch_interval as u64);
+
         Ok(TSSA {
-            iterations: self.iterations.context("Missing iterations parameters")?,
+            termination: TerminationCriterion::with_iterations(self.termination, iterations)
+                .context("Missing iterations parameters")?,
             tabu_list_size: self
                 .tabu_list_size
                 .context("Missing tabu list size parameter")?,
             logger: self.logger.context("No logger parameter")?,
-            algorithm_switch_interval: self
-                .algorithm_switch_interval
-                .context("No algorithm switch interval")?,
+            algorithm_switch_interval,
             cooling_schedule: self.cooling_schedule.context("No cooling schedule")?,
             criterion_operator: self.criterion_operator.context("No criterion operator")?,
             neighborhood_operator: self
                 .neighborhood_operator
                 .context("No neighborhood operator")?,
+            evaluation: self.evaluation.unwrap_or_default(),
+            seed: self.seed.unwrap_or_else(random_seed),
         })
     }
 }
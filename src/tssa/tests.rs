@@ -4,7 +4,7 @@ use crate::{
     logger::CSVLogger,
     neighbor::{InverseNeighborhoodOperator, SwapNeighborhoodOperator},
     simulated_annealing::{
-        cooling_schedule::{ExponentialCoolingScheduleBuilder, LinearCoolingSchedule},
+        cooling_schedule::ExponentialCoolingScheduleBuilder,
         criterion_operator::BoltzmanProbabilityCriterionOperator,
     },
     solver::Solver,
@@ -28,7 +28,7 @@ pub fn get_tssa_config(instance: &str) -> Result<Vec<Box<dyn Solver>>> {
                 .iterations(20)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.990f32)
                         .build()?,
                 ))
@@ -47,7 +47,7 @@ pub fn get_tssa_config(instance: &str) -> Result<Vec<Box<dyn Solver>>> {
                 .iterations(20)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.999f32)
                         .build()?,
                 ))
@@ -63,7 +63,7 @@ pub fn get_tssa_config(instance: &str) -> Result<Vec<Box<dyn Solver>>> {
                 .iterations(20)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.999f32)
                         .build()?,
                 ))
@@ -79,7 +79,7 @@ pub fn get_tssa_config(instance: &str) -> Result<Vec<Box<dyn Solver>>> {
                 .iterations(20)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.999f32)
                         .build()?,
                 ))
@@ -95,7 +95,7 @@ pub fn get_tssa_config(instance: &str) -> Result<Vec<Box<dyn Solver>>> {
                 .iterations(20)
                 .cooling_schedule(Box::new(
                     ExponentialCoolingScheduleBuilder::default()
-                        .initial_temperature(1f32)
+                        .initial_temperature(100f32)
                         .cooling_factor(0.999f32)
                         .build()?,
                 ))
@@ -0,0 +1,199 @@
+use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::problem::Problem;
+use crate::problem_loader::{
+    distance::{distance_matrix, DistanceFunction, DistancePrecision},
+    fleet::FleetLimit,
+    solution_file::SolutionFile,
+    Coordinates, Demand,
+};
+use anyhow::{anyhow, Context, Result};
+use rand::RngCore;
+use std::fs::read_to_string;
+
+pub mod parser;
+pub mod schedule;
+#[cfg(test)]
+mod tests;
+
+pub type Time = Fitness;
+
+// Node of a Solomon instance, the depot is the customer number 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Customer {
+    pub coordinates: Coordinates,
+    pub demand: Demand,
+    pub ready_time: Time,
+    pub due_date: Time,
+    pub service_time: Time,
+}
+
+// How violated time windows are handled by the evaluation
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TimeWindows {
+    // Routes are closed before a window would be missed, solutions still late get the worst cost
+    #[default]
+    Hard,
+    // Every unit of lateness adds the penalty to the cost
+    Penalized {
+        lateness_penalty: Fitness,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct VRPTWProblem {
+    name: String,
+    vehicles: usize,
+    capacity: Demand,
+    // Customer numbers are used as genes, so the depot is always at index 0
+    customers: Vec<Customer>,
+    distances: Vec<Vec<Fitness>>,
+    time_windows: TimeWindows,
+    fleet_limit: FleetLimit,
+}
+
+impl Problem for VRPTWProblem {
+    fn random_individual(&self, rng: &mut dyn RngCore) -> VecIndividual {
+        let stops = self.stops();
+        let mut left_nodes = VecIndividual::from(&stops);
+        let mut selected_nodes: VecIndividual = VecIndividual::new();
+
+        for _ in &stops {
+            let selected_gene = left_nodes.random_gene(rng);
+            selected_nodes.add_gene(selected_gene);
+            left_nodes.remove_gene(selected_gene)
+        }
+
+        selected_nodes
+    }
+
+    fn eval(&self, individual: &VecIndividual) -> Result<Fitness> {
+        self.total_cost(individual)
+    }
+
+    fn serialize_indiviual(&self, individual: &VecIndividual) -> String {
+        self.solution_file(individual)
+            .map(|solution| solution.to_string())
+            .unwrap_or_else(|err| format!("Invalid individual: {}", err))
+    }
+}
+
+impl VRPTWProblem {
+    pub fn new(
+        name: String,
+        vehicles: usize,
+        capacity: Demand,
+        customers: Vec<Customer>,
+    ) -> VRPTWProblem {
+        let mut problem = VRPTWProblem {
+            name,
+            vehicles,
+            capacity,
+            customers,
+            ..VRPTWProblem::default()
+        };
+        problem.precalculate_distances();
+        problem
+    }
+
+    pub fn from_file(path: &str) -> Result<VRPTWProblem> {
+        let content = read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        VRPTWProblem::parse(&content).with_context(|| format!("Failed to parse {}", path))
+    }
+
+    // Distance increased by the lateness and fleet limit penalties
+    pub fn total_cost(&self, individual: &VecIndividual) -> Result<Fitness> {
+        let routes = self.decode(individual)?;
+        let distance: Fitness = routes.iter().map(|route| route.length).sum();
+        let lateness: Time = routes.iter().map(|route| route.lateness).sum();
+
+        let lateness_cost = match self.time_windows {
+            TimeWindows::Hard if lateness > 0.0 => Fitness::INFINITY,
+            TimeWindows::Hard => Fitness::default(),
+            TimeWindows::Penalized { lateness_penalty } => lateness * lateness_penalty,
+        };
+
+        Ok(distance + lateness_cost + self.fleet_limit.penalty(routes.len()))
+    }
+
+    pub fn total_distance(&self, individual: &VecIndividual) -> Result<Fitness> {
+        Ok(self
+            .decode(individual)?
+            .iter()
+            .map(|route| route.length)
+            .sum())
+    }
+
+    pub fn solution_file(&self, individual: &VecIndividual) -> Result<SolutionFile> {
+        let routes = self.decode(individual)?;
+
+        Ok(SolutionFile {
+            vehicles: Some(routes.len()),
+            cost: Some(routes.iter().map(|route| route.length).sum()),
+            routes: routes
+                .into_iter()
+                .map(|route| route.stops.iter().map(|stop| *stop as usize).collect())
+                .collect(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Number of vehicles available in the instance file
+    pub fn vehicles(&self) -> usize {
+        self.vehicles
+    }
+
+    pub fn capacity(&self) -> Demand {
+        self.capacity
+    }
+
+    pub fn customers(&self) -> &Vec<Customer> {
+        &self.customers
+    }
+
+    pub fn stops(&self) -> Vec<Gene> {
+        (1..self.customers.len() as Gene).collect()
+    }
+
+    pub fn time_windows(&self) -> TimeWindows {
+        self.time_windows
+    }
+
+    pub fn set_time_windows(&mut self, time_windows: TimeWindows) {
+        self.time_windows = time_windows;
+    }
+
+    pub fn fleet_limit(&self) -> FleetLimit {
+        self.fleet_limit
+    }
+
+    pub fn set_fleet_limit(&mut self, fleet_limit: FleetLimit) {
+        self.fleet_limit = fleet_limit;
+    }
+
+    pub fn customer(&self, node: &Gene) -> Result<&Customer> {
+        self.customers
+            .get(*node as usize)
+            .with_context(|| format!("Customer {} does not exist", node))
+    }
+
+    // Travel times are equal to the distances, as in the Solomon benchmark
+    pub fn distance(&self, node_a: &Gene, node_b: &Gene) -> Result<Fitness> {
+        self.distances
+            .get(*node_a as usize)
+            .and_then(|row| row.get(*node_b as usize))
+            .copied()
+            .ok_or(anyhow!("Failed to get distance, invalid node indexes"))
+    }
+
+    fn precalculate_distances(&mut self) {
+        let nodes: Vec<Coordinates> = self.customers.iter().map(|node| node.coordinates).collect();
+        self.distances = distance_matrix(
+            DistanceFunction::Euclidean2D,
+            &nodes,
+            DistancePrecision::Exact,
+        );
+    }
+}
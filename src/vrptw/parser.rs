@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Result};
+
+use crate::parsing::parse_number;
+use crate::problem_loader::Coordinates;
+
+use super::{Customer, VRPTWProblem};
+
+#[derive(Copy, Clone, PartialEq)]
+enum Section {
+    Name,
+    Vehicle,
+    Customer,
+}
+
+impl VRPTWProblem {
+    // Reads Solomon and Homberger instances: the name, a VEHICLE section with
+    // "NUMBER CAPACITY" and a CUSTOMER section with one row per node starting with the depot
+    pub fn parse(content: &str) -> Result<VRPTWProblem> {
+        let mut section = Section::Name;
+        let mut name = String::new();
+        let mut fleet = None;
+        let mut customers = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            let row = line.split_whitespace().collect::<Vec<&str>>();
+
+            match (section, line) {
+                (_, "") => continue,
+                (_, "VEHICLE") => section = Section::Vehicle,
+                (_, "CUSTOMER") => section = Section::Customer,
+                (Section::Name, _) => name = line.to_string(),
+                // Column headers
+                _ if !line.starts_with(|first: char| first.is_ascii_digit()) => continue,
+                (Section::Vehicle, _) => {
+                    let [vehicles, capacity] = row[..] else {
+                        return Err(anyhow!("line {}: malformed row '{}'", line_number, line));
+                    };
+                    fleet = Some((
+                        parse_number(vehicles, line_number)?,
+                        parse_number(capacity, line_number)?,
+                    ));
+                }
+                (Section::Customer, _) => {
+                    let [number, x, y, demand, ready_time, due_date, service_time] = row[..] else {
+                        return Err(anyhow!("line {}: malformed row '{}'", line_number, line));
+                    };
+                    if parse_number::<usize>(number, line_number)? != customers.len() {
+                        return Err(anyhow!(
+                            "line {}: expected customer {}, found {}",
+                            line_number,
+                            customers.len(),
+                            number
+                        ));
+                    }
+                    customers.push(Customer {
+                        coordinates: Coordinates {
+                            x: parse_number(x, line_number)?,
+                            y: parse_number(y, line_number)?,
+                        },
+                        demand: parse_number(demand, line_number)?,
+                        ready_time: parse_number(ready_time, line_number)?,
+                        due_date: parse_number(due_date, line_number)?,
+                        service_time: parse_number(service_time, line_number)?,
+                    });
+                }
+            }
+        }
+
+        let (vehicles, capacity) = fleet.ok_or(anyhow!("missing VEHICLE section"))?;
+        if customers.len() < 2 {
+            return Err(anyhow!(
+                "CUSTOMER section needs a depot and at least one customer"
+            ));
+        }
+        if let Some((number, customer)) = customers
+            .iter()
+            .enumerate()
+            .find(|(_, customer)| customer.demand > capacity)
+        {
+            return Err(anyhow!(
+                "customer {} demands {}, which exceeds the vehicle capacity {}",
+                number,
+                customer.demand,
+                capacity
+            ));
+        }
+
+        Ok(VRPTWProblem::new(name, vehicles, capacity, customers))
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+
+use crate::individual::{Fitness, Gene, VecIndividual};
+use crate::problem_loader::Demand;
+
+use super::{Time, TimeWindows, VRPTWProblem};
+
+// Single vehicle trip with the times it reaches every customer
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedRoute {
+    pub stops: Vec<Gene>,
+    pub arrivals: Vec<Time>,
+    pub load: Demand,
+    pub length: Fitness,
+    pub waiting: Time,
+    // Sum of arrivals after the due dates, including the return to the depot
+    pub lateness: Time,
+}
+
+impl VRPTWProblem {
+    // Splits the giant tour whenever the next customer exceeds the capacity or,
+    // with hard time windows, can no longer be served on time. With penalized
+    // windows a new route is opened whenever it is cheaper than extending the last one.
+    pub fn decode(&self, individual: &VecIndividual) -> Result<Vec<TimedRoute>> {
+        let mut routes: Vec<Vec<Gene>> = Vec::new();
+        // Load and departure time of the last stop of the open route
+        let mut load: Demand = 0;
+        let mut time = Time::default();
+
+        for gene in individual.genes() {
+            let customer = self.customer(gene)?;
+
+            if let Some(route) = routes.last_mut() {
+                let previous = route.last().context("Routes are never empty")?;
+                let arrival = time + self.distance(previous, gene)?;
+                let departure = arrival.max(customer.ready_time) + customer.service_time;
+                let fits = load.saturating_add(customer.demand) <= self.capacity;
+
+                let extended = fits
+                    && match self.time_windows {
+                        TimeWindows::Hard => {
+                            arrival <= customer.due_date
+                                && departure + self.distance(gene, &0)?
+                                    <= self.customers[0].due_date
+                        }
+                        TimeWindows::Penalized { lateness_penalty } => {
+                            let cost = |route: TimedRoute| {
+                                route.length + route.lateness * lateness_penalty
+                            };
+                            cost(self.schedule([route.as_slice(), &[*gene]].concat())?)
+                                <= cost(self.schedule(route.clone())?)
+                                    + cost(self.schedule(vec![*gene])?)
+                        }
+                    };
+
+                if extended {
+                    load += customer.demand;
+                    time = departure;
+                    route.push(*gene);
+                    continue;
+                }
+            }
+
+            let arrival = self.customers[0].ready_time + self.distance(&0, gene)?;
+            load = customer.demand;
+            time = arrival.max(customer.ready_time) + customer.service_time;
+            routes.push(vec![*gene]);
+        }
+
+        routes
+            .into_iter()
+            .map(|stops| self.schedule(stops))
+            .collect()
+    }
+
+    // Vehicles leave the depot at its ready time and wait for windows that are not open yet
+    pub fn schedule(&self, stops: Vec<Gene>) -> Result<TimedRoute> {
+        let depot = &self.customers[0];
+        let mut route = TimedRoute {
+            arrivals: Vec::with_capacity(stops.len()),
+            load: 0,
+            length: Fitness::default(),
+            waiting: Time::default(),
+            lateness: Time::default(),
+            stops: Vec::new(),
+        };
+        let mut time = depot.ready_time;
+        let mut previous: Gene = 0;
+
+        for stop in stops.iter().chain([0].iter()) {
+            let customer = self.customer(stop)?;
+            let travel = self.distance(&previous, stop)?;
+            let arrival = time + travel;
+
+            route.length += travel;
+            route.lateness += (arrival - customer.due_date).max(0.0);
+            if *stop == 0 {
+                break;
+            }
+
+            route.waiting += (customer.ready_time - arrival).max(0.0);
+            route.load = route.load.saturating_add(customer.demand);
+            route.arrivals.push(arrival);
+            time = arrival.max(customer.ready_time) + customer.service_time;
+            previous = *stop;
+        }
+
+        route.stops = stops;
+        Ok(route)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{individual::VecIndividual, problem::Problem};
+
+use super::{TimeWindows, VRPTWProblem};
+
+const SOLOMON_INSTANCE: &str = "TOY
+
+VEHICLE
+NUMBER     CAPACITY
+  3          30
+
+CUSTOMER
+CUST NO.  XCOORD.   YCOORD.    DEMAND   READY TIME  DUE DATE   SERVICE   TIME
+
+    0       0          0          0          0        100          0
+    1      10          0         10          0         20          5
+    2      20          0         10          0         30          5
+    3       0         10         10         50         60          5
+";
+
+#[test]
+fn parse_solomon_instance() {
+    let problem = VRPTWProblem::parse(SOLOMON_INSTANCE).unwrap();
+
+    assert_eq!(problem.name(), "TOY");
+    assert_eq!(problem.vehicles(), 3);
+    assert_eq!(problem.capacity(), 30);
+    assert_eq!(problem.stops(), vec![1, 2, 3]);
+    assert_eq!(problem.customers()[3].ready_time, 50.0);
+    assert_eq!(problem.distance(&1, &2).unwrap(), 10.0);
+
+    let error = VRPTWProblem::parse("TOY\nVEHICLE\n3 30\nCUSTOMER\n0 0 0 0 0 100\n").unwrap_err();
+    assert!(error.to_string().starts_with("line 5"));
+
+    let error = VRPTWProblem::parse(&SOLOMON_INSTANCE.replace("  3          30", "  3          8"))
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "customer 1 demands 10, which exceeds the vehicle capacity 8"
+    );
+}
+
+#[test]
+fn schedule_tracks_arrivals_and_waiting() {
+    let problem = VRPTWProblem::parse(SOLOMON_INSTANCE).unwrap();
+    let route = problem.schedule(vec![3, 1]).unwrap();
+
+    assert_eq!(route.arrivals[0], 10.0);
+    assert_eq!(route.waiting, 40.0);
+    // Customer 1 is reached long after its due date of 20
+    assert!(route.lateness > 49.0);
+}
+
+#[test]
+fn hard_windows_start_new_routes() {
+    let problem = VRPTWProblem::parse(SOLOMON_INSTANCE).unwrap();
+
+    let routes = problem.decode(&VecIndividual::from(vec![1, 2, 3])).unwrap();
+    assert_eq!(routes.len(), 1);
+
+    let routes = problem.decode(&VecIndividual::from(vec![3, 1, 2])).unwrap();
+    let stops: Vec<Vec<u16>> = routes.iter().map(|route| route.stops.clone()).collect();
+    assert_eq!(stops, vec![vec![3], vec![1, 2]]);
+    assert!(routes.iter().all(|route| route.lateness == 0.0));
+    assert!(problem.eval(&VecIndividual::from(vec![3, 1, 2])).unwrap() > 0.0);
+}
+
+#[test]
+fn penalized_windows_add_lateness_to_the_cost() {
+    let mut problem = VRPTWProblem::parse(SOLOMON_INSTANCE).unwrap();
+    problem.set_time_windows(TimeWindows::Penalized {
+        lateness_penalty: 0.01,
+    });
+    let individual = VecIndividual::from(vec![3, 1, 2]);
+
+    // Being late is cheaper than driving back to the depot
+    let routes = problem.decode(&individual).unwrap();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(
+        problem.total_cost(&individual).unwrap(),
+        routes[0].length + 0.01 * routes[0].lateness
+    );
+
+    // Customer 1 is served on time by a new route once lateness gets expensive
+    problem.set_time_windows(TimeWindows::Penalized {
+        lateness_penalty: 2.0,
+    });
+    let routes = problem.decode(&individual).unwrap();
+    let stops: Vec<Vec<u16>> = routes.iter().map(|route| route.stops.clone()).collect();
+    assert_eq!(stops, vec![vec![3], vec![1, 2]]);
+    assert!(routes.iter().all(|route| route.lateness == 0.0));
+    assert_eq!(
+        problem.total_cost(&individual).unwrap(),
+        routes.iter().map(|route| route.length).sum::<f64>()
+    );
+}